@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use helium::core::validate::validate_assets;
+
+const USAGE: &str = "usage: helium-cli validate [--assets <dir>] [--action <name>]...
+
+  --assets <dir>   โฟลเดอร์ asset (ค่าเริ่มต้น `assets`)
+  --action <name>  ชื่อ custom action ที่เกมลงทะเบียนไว้ (ใส่ซ้ำได้)";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("validate") {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    let mut assets = PathBuf::from("assets");
+    let mut custom_actions = HashSet::new();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--assets", Some(dir)) => assets = PathBuf::from(dir),
+            ("--action", Some(name)) => {
+                custom_actions.insert(name);
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let problems = validate_assets(&assets, &custom_actions);
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    // คำเตือนไม่ทำให้ล้มเหลว
+    let warnings = problems.iter().filter(|problem| problem.warning).count();
+    let errors = problems.len() - warnings;
+    if errors == 0 {
+        match warnings {
+            0 => println!("ไม่พบปัญหาใน {}", assets.display()),
+            _ => println!("ไม่พบปัญหาใน {} (คำเตือน {} รายการ)", assets.display(), warnings),
+        }
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("พบปัญหา {} รายการ (คำเตือน {} รายการ)", errors, warnings);
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,476 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use crate::core::game_state::{ChangeStateEvent, GameState};
+use crate::core::story::condition::Condition;
+use crate::core::story::variables::StoryValue;
+use crate::types::JumpTarget;
+use crate::util::identifier::audio;
+
+/// Action ที่ผูกกับ `DialogEntry.actions` และทำงานเมื่อผู้เล่นกดผ่าน entry นั้น
+///
+/// เขียนใน JSON ได้ทั้งแบบสั้น `"change_scene:school"` และแบบ object
+/// `{"type": "shake", "intensity": 12.0, "duration": 0.4}`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogAction {
+    SetVar { name: String, value: StoryValue },
+    /// บวกค่าเข้าตัวแปร int (`add_var:affection.miki=1`, ค่าติดลบเพื่อลด)
+    AddVar { name: String, amount: i64 },
+    /// กระโดดไป entry อื่นทันทีถ้าเงื่อนไขเป็นจริง (`jump:lunch if met_miki`)
+    Jump { target: JumpTarget, condition: Option<Condition> },
+    /// จบเรื่องและกลับไปเมนูหลัก
+    End,
+    /// ไปยัง scene อื่น เริ่มที่ label ถ้ามี (`change_scene:school#lunch`)
+    ChangeScene { scene: String, label: Option<String> },
+    /// เข้า sub-scene แล้วกลับมาที่ entry ถัดจากผู้เรียกเมื่อเจอ `return` หรือจบ scene (`call_scene:shop#greeting`)
+    CallScene { scene: String, label: Option<String> },
+    /// กลับไปยัง scene ที่ `call_scene` เข้ามา
+    Return,
+    PlayBgm { path: String, volume: f32 },
+    StopBgm,
+    Wait { seconds: f32 },
+    Shake { intensity: f32, duration: f32 },
+    /// Action ที่เกมลงทะเบียนเองผ่าน `VNPlugin::with_action`
+    Custom { name: String, args: Vec<String> },
+}
+
+const DEFAULT_BGM_VOLUME: f32 = 1.0;
+const DEFAULT_SHAKE_INTENSITY: f32 = 10.0;
+const DEFAULT_SHAKE_DURATION: f32 = 0.5;
+/// เพดานของค่าที่เขียนใน script (ค่าที่เกินถูกลดลงมา)
+const MAX_WAIT_SECONDS: f32 = 3600.0;
+const MAX_SHAKE_INTENSITY: f32 = 100.0;
+const MAX_SHAKE_DURATION: f32 = 10.0;
+
+impl DialogAction {
+    /// แปลงรูปแบบสั้น `name:arg1,arg2`
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let (name, args) = match source.split_once(':') {
+            Some((name, args)) => (name.trim(), args.trim()),
+            None => (source.trim(), ""),
+        };
+
+        let args: Vec<String> = if args.is_empty() {
+            Vec::new()
+        } else {
+            args.split(',').map(|arg| arg.trim().to_string()).collect()
+        };
+
+        Self::from_args(name, &args)
+    }
+
+    /// สร้างจากชื่อและ argument ที่แยกแล้ว (ใช้ตอน argument มี `,` หรือช่องว่างในเครื่องหมายคำพูด)
+    pub(crate) fn from_args(name: &str, args: &[String]) -> Result<Self, String> {
+        let arg = |index: usize| args.get(index).map(String::as_str);
+
+        match name {
+            "set_var" => {
+                let (var, value) = arg(0)
+                    .and_then(|a| a.split_once('='))
+                    .ok_or_else(|| "set_var expects `name=value`".to_string())?;
+                Ok(DialogAction::SetVar {
+                    name: var.trim().to_string(),
+                    value: StoryValue::parse(value),
+                })
+            }
+            "add_var" => {
+                let (var, amount) = arg(0)
+                    .and_then(|a| a.split_once('='))
+                    .ok_or_else(|| "add_var expects `name=amount`".to_string())?;
+                Ok(DialogAction::AddVar {
+                    name: var.trim().to_string(),
+                    amount: amount
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_| format!("add_var amount must be an integer, got `{}`", amount.trim()))?,
+                })
+            }
+            "jump" => {
+                let target = arg(0).ok_or_else(|| "jump expects a target".to_string())?;
+                let (target, condition) = match target.split_once(" if ") {
+                    Some((target, condition)) => {
+                        let condition = Condition::parse(condition.trim()).map_err(|e| e.to_string())?;
+                        (target, Some(condition))
+                    }
+                    None => (target, None),
+                };
+                let target = match target.trim().parse::<usize>() {
+                    Ok(stage) => JumpTarget::Stage(stage),
+                    Err(_) => JumpTarget::parse(target),
+                };
+                Ok(DialogAction::Jump { target, condition })
+            }
+            "end" => Ok(DialogAction::End),
+            "change_scene" | "call_scene" => {
+                let target = arg(0).ok_or_else(|| format!("{} expects a scene name", name))?;
+                let (scene, label) = match target.split_once('#') {
+                    Some((scene, label)) => (scene.to_string(), Some(label).filter(|l| !l.is_empty()).map(str::to_string)),
+                    None => (target.to_string(), None),
+                };
+                Ok(match name {
+                    "call_scene" => DialogAction::CallScene { scene, label },
+                    _ => DialogAction::ChangeScene { scene, label },
+                })
+            }
+            "return" => Ok(DialogAction::Return),
+            "play_bgm" => {
+                let path = arg(0).ok_or_else(|| "play_bgm expects an audio path".to_string())?;
+                Ok(DialogAction::PlayBgm {
+                    path: path.to_string(),
+                    volume: parse_f32(arg(1), DEFAULT_BGM_VOLUME, "play_bgm volume")?,
+                })
+            }
+            "stop_bgm" => Ok(DialogAction::StopBgm),
+            "wait" => Ok(DialogAction::Wait {
+                seconds: parse_f32(arg(0), 0.0, "wait seconds")?.min(MAX_WAIT_SECONDS),
+            }),
+            "shake" => Ok(DialogAction::Shake {
+                intensity: parse_f32(arg(0), DEFAULT_SHAKE_INTENSITY, "shake intensity")?.min(MAX_SHAKE_INTENSITY),
+                duration: parse_f32(arg(1), DEFAULT_SHAKE_DURATION, "shake duration")?.min(MAX_SHAKE_DURATION),
+            }),
+            "" => Err("empty action".to_string()),
+            _ => Ok(DialogAction::Custom {
+                name: name.to_string(),
+                args: args.to_vec(),
+            }),
+        }
+    }
+
+    fn from_object(kind: &str, fields: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        let field = |key: &str| fields.get(key).map(json_to_arg);
+
+        let args: Vec<String> = match kind {
+            "set_var" => {
+                let name = field("name").ok_or("set_var expects `name`")?;
+                let value = fields.get("value").ok_or("set_var expects `value`")?;
+                return Ok(DialogAction::SetVar { name, value: json_to_value(value)? });
+            }
+            "add_var" => {
+                let name = field("name").ok_or("add_var expects `name`")?;
+                let amount = field("amount").ok_or("add_var expects `amount`")?;
+                vec![format!("{}={}", name, amount)]
+            }
+            "jump" => {
+                let target = field("target").ok_or("jump expects `target`")?;
+                match field("condition") {
+                    Some(condition) => vec![format!("{} if {}", target, condition)],
+                    None => vec![target],
+                }
+            }
+            "end" => Vec::new(),
+            "change_scene" => vec![field("scene").ok_or("change_scene expects `scene`")?],
+            "call_scene" => vec![field("scene").ok_or("call_scene expects `scene`")?],
+            "return" => Vec::new(),
+            "play_bgm" => {
+                let mut args = vec![field("path").ok_or("play_bgm expects `path`")?];
+                args.extend(field("volume"));
+                args
+            }
+            "wait" => field("seconds").into_iter().collect(),
+            "shake" => vec![
+                field("intensity").unwrap_or_else(|| DEFAULT_SHAKE_INTENSITY.to_string()),
+                field("duration").unwrap_or_else(|| DEFAULT_SHAKE_DURATION.to_string()),
+            ],
+            _ => match fields.get("args") {
+                Some(serde_json::Value::Array(values)) => values.iter().map(json_to_arg).collect(),
+                Some(value) => vec![json_to_arg(value)],
+                None => Vec::new(),
+            },
+        };
+
+        Self::from_args(kind, &args)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DialogAction::SetVar { .. } => "set_var",
+            DialogAction::AddVar { .. } => "add_var",
+            DialogAction::Jump { .. } => "jump",
+            DialogAction::End => "end",
+            DialogAction::ChangeScene { .. } => "change_scene",
+            DialogAction::CallScene { .. } => "call_scene",
+            DialogAction::Return => "return",
+            DialogAction::PlayBgm { .. } => "play_bgm",
+            DialogAction::StopBgm => "stop_bgm",
+            DialogAction::Wait { .. } => "wait",
+            DialogAction::Shake { .. } => "shake",
+            DialogAction::Custom { name, .. } => name,
+        }
+    }
+}
+
+/// ตัวเลขที่ไม่ติดลบ (`inf`/`NaN` ใช้ไม่ได้ เพราะ `Timer::from_seconds` จะ panic ตอน action ทำงาน)
+fn parse_f32(raw: Option<&str>, default: f32, what: &str) -> Result<f32, String> {
+    match raw {
+        Some(value) => value
+            .parse::<f32>()
+            .ok()
+            .filter(|number| number.is_finite() && *number >= 0.0)
+            .ok_or_else(|| format!("{} must be a non-negative number, got `{}`", what, value)),
+        None => Ok(default),
+    }
+}
+
+/// ค่าของ `set_var` ตามชนิดใน JSON (`"5"` ยังเป็น string ไม่ถูกแปลงเป็นตัวเลข)
+fn json_to_value(value: &serde_json::Value) -> Result<StoryValue, String> {
+    match value {
+        serde_json::Value::Bool(value) => Ok(StoryValue::Bool(*value)),
+        serde_json::Value::Number(number) => number
+            .as_i64()
+            .map(StoryValue::Int)
+            .ok_or_else(|| format!("set_var value `{}` is not an integer", number)),
+        serde_json::Value::String(value) => Ok(StoryValue::Str(value.clone())),
+        other => Err(format!("set_var value must be a bool, integer or string, got `{}`", other)),
+    }
+}
+
+fn json_to_arg(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// รูปแบบสั้นที่ใช้ทั้งตอน serialize และแสดงใน log
+impl fmt::Display for DialogAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // string ใส่ `"` เสมอ ค่าอย่าง `"5"` หรือ `"true"` จึงไม่กลายเป็น int/bool ตอนอ่านกลับ
+            DialogAction::SetVar { name, value: StoryValue::Str(value) } => write!(f, "set_var:{}=\"{}\"", name, value),
+            DialogAction::SetVar { name, value } => write!(f, "set_var:{}={}", name, value),
+            DialogAction::AddVar { name, amount } => write!(f, "add_var:{}={}", name, amount),
+            DialogAction::Jump { target, condition: None } => write!(f, "jump:{}", target),
+            DialogAction::Jump { target, condition: Some(condition) } => {
+                write!(f, "jump:{} if {}", target, condition)
+            }
+            DialogAction::End => write!(f, "end"),
+            DialogAction::ChangeScene { scene, label: None } => write!(f, "change_scene:{}", scene),
+            DialogAction::ChangeScene { scene, label: Some(label) } => {
+                write!(f, "change_scene:{}#{}", scene, label)
+            }
+            DialogAction::CallScene { scene, label: None } => write!(f, "call_scene:{}", scene),
+            DialogAction::CallScene { scene, label: Some(label) } => write!(f, "call_scene:{}#{}", scene, label),
+            DialogAction::Return => write!(f, "return"),
+            DialogAction::PlayBgm { path, volume } => write!(f, "play_bgm:{},{}", path, volume),
+            DialogAction::StopBgm => write!(f, "stop_bgm"),
+            DialogAction::Wait { seconds } => write!(f, "wait:{}", seconds),
+            DialogAction::Shake { intensity, duration } => write!(f, "shake:{},{}", intensity, duration),
+            DialogAction::Custom { name, args } if args.is_empty() => write!(f, "{}", name),
+            DialogAction::Custom { name, args } => write!(f, "{}:{}", name, args.join(",")),
+        }
+    }
+}
+
+impl Serialize for DialogAction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DialogAction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawAction {
+            Short(String),
+            Full {
+                #[serde(rename = "type")]
+                kind: String,
+                #[serde(flatten)]
+                fields: HashMap<String, serde_json::Value>,
+            },
+        }
+
+        match RawAction::deserialize(deserializer)? {
+            RawAction::Short(source) => DialogAction::parse(&source),
+            RawAction::Full { kind, fields } => DialogAction::from_object(&kind, &fields),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+/// Handler ของ custom action: ได้รับ `World` และ argument ที่เขียนไว้ใน dialog
+pub type ActionHandler = Arc<dyn Fn(&mut World, &[String]) + Send + Sync>;
+
+/// Custom action ที่เกมลงทะเบียนไว้
+#[derive(Resource, Clone, Default)]
+pub struct ActionRegistry {
+    handlers: HashMap<String, ActionHandler>,
+}
+
+impl ActionRegistry {
+    pub fn register(&mut self, name: &str, handler: ActionHandler) {
+        self.handlers.insert(name.to_string(), handler);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ActionHandler> {
+        self.handlers.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    pub fn names(&self) -> HashSet<String> {
+        self.handlers.keys().cloned().collect()
+    }
+}
+
+/// Action ที่ต้องทำงานนอก `process_stage_progression` (เสียง, wait, shake, custom)
+#[derive(Event, Clone)]
+pub struct DialogActionEvent {
+    pub action: DialogAction,
+}
+
+#[derive(Resource, Default)]
+pub struct PendingCustomActions {
+    actions: Vec<(String, Vec<String>)>,
+}
+
+/// หน่วงก่อนเริ่มบรรทัดถัดไป ระหว่างนี้ไม่รับ input และ typewriter หยุด
+#[derive(Resource, Default)]
+pub struct ActionWait {
+    timer: Option<Timer>,
+}
+
+impl ActionWait {
+    pub fn start(&mut self, seconds: f32) {
+        if seconds > 0.0 {
+            self.timer = Some(Timer::from_seconds(seconds, TimerMode::Once));
+        }
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.timer.is_some()
+    }
+
+    /// จบการหน่วงทันที (ใช้ตอนข้ามข้อความ)
+    pub fn finish(&mut self) {
+        self.timer = None;
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    intensity: f32,
+    timer: Option<Timer>,
+}
+
+#[derive(Component)]
+pub struct BackgroundMusic;
+
+pub fn handle_dialog_actions(
+    mut commands: Commands,
+    mut events: EventReader<DialogActionEvent>,
+    asset_server: Res<AssetServer>,
+    mut action_wait: ResMut<ActionWait>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut pending_custom: ResMut<PendingCustomActions>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+    music_query: Query<Entity, With<BackgroundMusic>>,
+) {
+    for event in events.read() {
+        match &event.action {
+            DialogAction::PlayBgm { path, volume } => {
+                for entity in music_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                commands.spawn((
+                    AudioBundle {
+                        source: audio(path).load::<AudioSource>(&asset_server),
+                        settings: PlaybackSettings::LOOP.with_volume(Volume::new(*volume)),
+                    },
+                    BackgroundMusic,
+                    Name::new("background_music"),
+                ));
+            }
+            DialogAction::StopBgm => {
+                for entity in music_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            DialogAction::Wait { seconds } => action_wait.start(*seconds),
+            DialogAction::Shake { intensity, duration } => {
+                screen_shake.intensity = *intensity;
+                screen_shake.timer = Some(Timer::from_seconds(*duration, TimerMode::Once));
+            }
+            DialogAction::Custom { name, args } => {
+                pending_custom.actions.push((name.clone(), args.clone()));
+            }
+            DialogAction::End => {
+                change_events.send(ChangeStateEvent {
+                    new_state: GameState::MainMenu,
+                });
+            }
+            DialogAction::SetVar { .. }
+            | DialogAction::AddVar { .. }
+            | DialogAction::Jump { .. }
+            | DialogAction::ChangeScene { .. }
+            | DialogAction::CallScene { .. }
+            | DialogAction::Return => {
+                warn!("Action `{}` should be handled by stage progression", event.action);
+            }
+        }
+    }
+}
+
+/// เรียก handler ของ custom action (ต้องใช้ exclusive access กับ World)
+pub fn run_custom_actions(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<PendingCustomActions>().actions);
+    if pending.is_empty() {
+        return;
+    }
+
+    let registry = world.resource::<ActionRegistry>().clone();
+    for (name, args) in pending {
+        match registry.get(&name) {
+            Some(handler) => handler(world, &args),
+            None => warn!("No handler registered for action `{}`", name),
+        }
+    }
+}
+
+pub fn tick_action_wait(time: Res<Time>, mut action_wait: ResMut<ActionWait>) {
+    let finished = action_wait
+        .timer
+        .as_mut()
+        .is_some_and(|timer| timer.tick(time.delta()).finished());
+
+    if finished {
+        action_wait.timer = None;
+    }
+}
+
+pub fn apply_screen_shake(
+    time: Res<Time>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let intensity = screen_shake.intensity;
+    let Some(timer) = screen_shake.timer.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+    let finished = timer.finished();
+    let strength = intensity * (1.0 - timer.fraction());
+    let t = time.elapsed_seconds();
+
+    for mut transform in camera_query.iter_mut() {
+        if finished {
+            transform.translation.x = 0.0;
+            transform.translation.y = 0.0;
+        } else {
+            transform.translation.x = (t * 73.0).sin() * strength;
+            transform.translation.y = (t * 59.0).cos() * strength;
+        }
+    }
+
+    if finished {
+        screen_shake.timer = None;
+    }
+}
@@ -0,0 +1,111 @@
+//! โหมด auto: บรรทัดที่พิมพ์จบแล้วไปต่อเองหลังรอตามความยาวข้อความ กด `InputAction::Auto` (A) เปิด/ปิด
+//!
+//! เวลารอ = `AUTO_BASE_DELAY` + จำนวน grapheme × เวลาอ่านต่อ grapheme ของภาษา ÷ `SettingsResource.auto_speed`
+//! (`{p}` กลางบรรทัดก็รอแบบเดียวกันโดยนับเฉพาะข้อความตั้งแต่ `{p}` ก่อนหน้า แล้วพิมพ์ต่อ)
+//! คลิกยังไปต่อได้ตามปกติ และโหมดปิดเองเมื่อถึงตัวเลือก
+
+use bevy::prelude::*;
+use crate::core::dialog::action::ActionWait;
+use crate::core::dialog::choice::ChoiceState;
+use crate::core::dialog::skip::SkipState;
+use crate::core::dialog::typewriter::TypewriterText;
+use crate::core::input::action::{ActionState, InputAction};
+use crate::core::language::manager::LanguageResource;
+use crate::core::resources::{DialogManager, SettingsResource, VNState};
+use crate::core::scene::transition::TransitionState;
+use crate::ui::dialog::DialogText;
+
+/// เวลารอขั้นต่ำหลังพิมพ์จบ (วินาที)
+const AUTO_BASE_DELAY: f32 = 1.0;
+/// ช่วงของ `SettingsResource.auto_speed`
+pub const MIN_AUTO_SPEED: f32 = 0.5;
+pub const MAX_AUTO_SPEED: f32 = 3.0;
+
+#[derive(Resource, Default)]
+pub struct AutoState {
+    pub enabled: bool,
+    /// ไปบรรทัดถัดไปใน frame นี้ (ตั้งโดย `update_auto` ใช้ใน `handle_text_interaction`)
+    pub advance: bool,
+    elapsed: f32,
+}
+
+impl AutoState {
+    pub fn stop(&mut self) {
+        self.enabled = false;
+        self.advance = false;
+        self.elapsed = 0.0;
+    }
+}
+
+/// เวลารอก่อนไปต่อของบรรทัดที่มี `graphemes` ตัว
+pub fn auto_delay(graphemes: usize, seconds_per_grapheme: f32, speed: f32) -> f32 {
+    (AUTO_BASE_DELAY + graphemes as f32 * seconds_per_grapheme) / speed.clamp(MIN_AUTO_SPEED, MAX_AUTO_SPEED)
+}
+
+pub fn handle_auto_input(actions: Res<ActionState>, mut auto: ResMut<AutoState>) {
+    if actions.just_pressed(InputAction::Auto) {
+        if auto.enabled {
+            auto.stop();
+        } else {
+            auto.enabled = true;
+        }
+    }
+}
+
+/// นับเวลาหลังพิมพ์จบหรือหยุดที่ `{p}` (ต้องรันก่อน `handle_text_interaction`)
+pub fn update_auto(
+    time: Res<Time>,
+    state: Res<VNState>,
+    settings: Res<SettingsResource>,
+    language_resource: Res<LanguageResource>,
+    choice_state: Res<ChoiceState>,
+    dialog_manager: Res<DialogManager>,
+    action_wait: Res<ActionWait>,
+    transition_state: Res<TransitionState>,
+    skip: Res<SkipState>,
+    mut auto: ResMut<AutoState>,
+    mut dialog_query: Query<&mut TypewriterText, With<DialogText>>,
+) {
+    auto.advance = false;
+    if !auto.enabled {
+        return;
+    }
+    if choice_state.active {
+        auto.stop();
+        return;
+    }
+
+    // หยุดที่ `{p}` ก็รอตามความยาวเหมือนจบบรรทัด แต่พิมพ์ต่อแทนการไปบรรทัดถัดไป
+    let ready = dialog_query
+        .get_single_mut()
+        .ok()
+        .filter(|typewriter| typewriter.waiting_for_click || typewriter.is_finished());
+    let Some(mut typewriter) = ready else {
+        auto.elapsed = 0.0;
+        return;
+    };
+    if skip.is_active()
+        || state.should_reset_dialog()
+        || dialog_manager.is_processing()
+        || action_wait.is_waiting()
+        || transition_state.is_playing()
+    {
+        auto.elapsed = 0.0;
+        return;
+    }
+
+    auto.elapsed += time.delta_seconds();
+    let delay = auto_delay(
+        typewriter.unread_grapheme_count(),
+        language_resource.current_language.reading_time_per_grapheme(),
+        settings.auto_speed,
+    );
+    if auto.elapsed >= delay {
+        auto.elapsed = 0.0;
+        if typewriter.waiting_for_click {
+            typewriter.resume();
+        } else {
+            auto.advance = true;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use crate::core::dialog::interpolate::{character_name, interpolate};
+use crate::core::dialog::typewriter::plain_text;
+use crate::core::story::variables::StoryVariables;
+use crate::types::DialogScene;
+
+/// จำนวนรายการสูงสุดใน backlog (เก่าสุดถูกทิ้งก่อน)
+const MAX_BACKLOG_ENTRIES: usize = 200;
+
+/// บรรทัดหรือตัวเลือกที่ผู้เล่นเห็นในรอบนี้ เก็บตำแหน่งเพื่อแสดงใหม่ตามภาษาปัจจุบัน
+#[derive(Debug, Clone)]
+pub struct BacklogEntry {
+    pub scene: String,
+    pub stage: usize,
+    /// index ใน `DialogEntry.choices` ถ้าเป็นตัวเลือกที่เลือก
+    pub choice: Option<usize>,
+    /// ตัวแปรตอนที่แสดง ข้อความที่แทนค่าตัวแปรจึงตรงกับที่ผู้เล่นเห็นตอนนั้น (เหมือน `HistoryStep`)
+    pub variables: StoryVariables,
+}
+
+/// ประวัติบทสนทนาของรอบที่เล่นอยู่ (ไม่บันทึกลง save)
+#[derive(Resource, Default)]
+pub struct Backlog {
+    entries: Vec<BacklogEntry>,
+}
+
+impl Backlog {
+    pub fn add_line(&mut self, scene: &str, stage: usize, variables: &StoryVariables) {
+        self.push(BacklogEntry { scene: scene.to_string(), stage, choice: None, variables: variables.clone() });
+    }
+
+    pub fn add_choice(&mut self, scene: &str, stage: usize, choice_index: usize, variables: &StoryVariables) {
+        self.push(BacklogEntry {
+            scene: scene.to_string(),
+            stage,
+            choice: Some(choice_index),
+            variables: variables.clone(),
+        });
+    }
+
+    /// ตัดรายการตั้งแต่ครั้งล่าสุดที่แสดงบรรทัดนี้ออก (ใช้ตอน rollback บรรทัดนั้นจะถูกเพิ่มใหม่เมื่อแสดง)
+    pub fn rewind_to(&mut self, scene: &str, stage: usize) {
+        if let Some(index) = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.choice.is_none() && entry.scene == scene && entry.stage == stage)
+        {
+            self.entries.truncate(index);
+        }
+    }
+
+    pub fn entries(&self) -> &[BacklogEntry] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn push(&mut self, entry: BacklogEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_BACKLOG_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+}
+
+impl BacklogEntry {
+    /// ชื่อผู้พูดและข้อความในภาษาที่เลือก (ตัด markup และ tag ออก) หรือ `None` ถ้าหา entry ไม่พบ
+    pub fn render(&self, scene: &DialogScene, language: &str) -> Option<(Option<String>, String)> {
+        let variables = &self.variables;
+        let entry = scene.entries.get(self.stage)?;
+        match self.choice {
+            Some(index) => {
+                let text = entry.choices.get(index)?.text.get(language)?;
+                Some((None, plain_text(&interpolate(text, variables, &scene.characters, language))))
+            }
+            None => {
+                let text = entry.text.get(language)?;
+                let speaker = character_name(&entry.character, variables, &scene.characters, language)
+                    .or_else(|| (!entry.character.is_empty()).then(|| entry.character.clone()));
+                Some((speaker, plain_text(&interpolate(text, variables, &scene.characters, language))))
+            }
+        }
+    }
+}
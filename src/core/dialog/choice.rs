@@ -1,17 +1,32 @@
 use bevy::prelude::*;
-use crate::core::resources::{VNState, DialogHistory};
-use crate::types::DialogChoice;
+use crate::core::dialog::action::{DialogAction, DialogActionEvent};
+use crate::core::dialog::backlog::Backlog;
+use crate::core::input::action::{ActionState, InputAction, CHOICE_ACTIONS};
+use crate::core::resources::{VNState, DialogHistory, DialogResource};
+use crate::core::scene::transition::TransitionState;
+use crate::core::story::variables::StoryVariables;
+use crate::types::{DialogChoice, DialogScene, JumpTarget};
 
 #[derive(Component)]
 pub struct ChoiceButton {
     pub choice_index: usize,
-    pub target_stage: usize,
+    pub target: JumpTarget,
+    pub available: bool,
+}
+
+/// ตัวเลือกที่ถูกแสดงจริง หลังจากกรองด้วย conditions แล้ว
+#[derive(Debug, Clone)]
+pub struct PresentedChoice {
+    /// index ใน `DialogEntry.choices`
+    pub index: usize,
+    pub choice: DialogChoice,
+    pub available: bool,
 }
 
 #[derive(Resource, Default)]
 pub struct ChoiceState {
     pub active: bool,
-    pub choices: Vec<DialogChoice>,
+    pub choices: Vec<PresentedChoice>,
     pub history: Vec<usize>,
 }
 
@@ -20,7 +35,7 @@ impl ChoiceState {
         self.history.push(choice_index);
     }
 
-    pub fn activate(&mut self, choices: Vec<DialogChoice>) {
+    pub fn activate(&mut self, choices: Vec<PresentedChoice>) {
         self.active = true;
         self.choices = choices;
     }
@@ -38,45 +53,53 @@ pub fn handle_choice_selection(
     mut state: ResMut<VNState>,
     mut choice_state: ResMut<ChoiceState>,
     mut history: ResMut<DialogHistory>,
+    mut backlog: ResMut<Backlog>,
+    variables: Res<StoryVariables>,
+    mut dialog_resource: ResMut<DialogResource>,
+    dialog_scenes: Res<Assets<DialogScene>>,
     choice_query: Query<(&ChoiceButton, &Interaction), Changed<Interaction>>,
     container_query: Query<Entity, With<crate::ui::choice::ChoiceContainer>>,
     overlay_query: Query<Entity, With<crate::ui::choice::ChoiceOverlay>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    transition_state: Res<TransitionState>,
+    mut action_events: EventWriter<DialogActionEvent>,
 ) {
-    if !choice_state.active {
+    if !choice_state.active || transition_state.is_playing() {
         return;
     }
 
-    let mut selected_choice: Option<(usize, usize)> = None;
-
-    let number_keys = [
-        KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
-        KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
-        KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
-    ];
+    let mut selected_choice: Option<(usize, JumpTarget)> = None;
 
-    for (i, key) in number_keys.iter().enumerate() {
-        if keyboard.just_pressed(*key) && i < choice_state.choices.len() {
-            let target_stage = choice_state.choices[i].target_stage;
-            selected_choice = Some((i, target_stage));
+    for number in 1..=CHOICE_ACTIONS {
+        let i = usize::from(number) - 1;
+        if actions.just_pressed(InputAction::Choice(number)) && i < choice_state.choices.len() {
+            let presented = &choice_state.choices[i];
+            if presented.available {
+                selected_choice = Some((presented.index, presented.choice.target.clone()));
+            }
             break;
         }
     }
 
     if selected_choice.is_none() {
         for (choice, interaction) in choice_query.iter() {
-            if *interaction == Interaction::Pressed {
-                selected_choice = Some((choice.choice_index, choice.target_stage));
+            if *interaction == Interaction::Pressed && choice.available {
+                selected_choice = Some((choice.choice_index, choice.target.clone()));
                 break;
             }
         }
     }
 
-    if let Some((choice_index, target_stage)) = selected_choice {
+    if let Some((choice_index, target)) = selected_choice {
+        let from_stage = state.stage;
+        backlog.add_choice(&state.current_scene, from_stage, choice_index, &variables);
         choice_state.add_choice(choice_index);
-        history.add_choice(state.stage, choice_index, target_stage);
         choice_state.deactivate();
-        state.change_stage(target_stage);
+        if !dialog_resource.jump(&target, &mut state, &dialog_scenes) {
+            // target หายไป (error ถูก log ไว้แล้ว): จบเรื่องแทนการค้างอยู่ที่ตัวเลือก
+            action_events.send(DialogActionEvent { action: DialogAction::End });
+        }
+        history.add_choice(from_stage, choice_index, state.stage);
 
         for entity in container_query.iter() {
             commands.entity(entity).despawn_recursive();
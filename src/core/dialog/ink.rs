@@ -0,0 +1,1007 @@
+//! Importer สำหรับ Ink ที่ compile แล้ว (`.ink.json` จาก inklecate / Inky)
+//!
+//! รองรับ subset ที่ใช้บ่อยในงาน visual novel:
+//! - knot / stitch กลายเป็น label `knot` / `knot.stitch` (กระโดดจาก scene อื่นด้วย `change_scene:<scene>#knot`)
+//! - choice `*` / `+` กลายเป็น `DialogChoice` พร้อม condition; choice แบบ once-only จำด้วยตัวแปร `ink_chosen.*`
+//! - fallback choice (`* -> knot`) กลายเป็น `auto_proceed` เมื่อไม่มีตัวเลือกให้เลือก
+//! - `VAR`, `~ x = 1`, `~ x += 1` และ conditional block กลายเป็น story variable และ `jump` action
+//! - tag `# speaker: miki`, `# bg: backgrounds/school.png`, `# show: miki left happy`, `# hide: miki`,
+//!   `# transition: crossfade 0.5`, `# language: thai`, `# character: miki "Miki" sprite=characters/miki.png`
+//!   และ tag ที่ตรงกับชื่อ action เช่น `# play_bgm: school.ogg`
+//!
+//! ตัวแปร `VAR` ถูกตั้งค่าเริ่มต้นทุกครั้งที่เริ่ม scene จาก entry แรก
+//! ค่าตัวแปรในข้อความ (`{gold}`) กลายเป็น placeholder `{var:gold}` ที่แทนค่าตอนแสดงผล
+//! ตัวละครที่ `# show` ไว้และ `# language` ส่งต่อไปยัง knot / stitch ที่ divert ไปถึง (knot ที่ไม่มีใคร divert มาเริ่มจากค่าเริ่มต้น)
+//!
+//! ส่วน function, tunnel, thread, read count และ list ยังไม่รองรับ
+//! และจะโหลดไม่ผ่านพร้อมบอก path ใน Ink
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use anyhow::{anyhow, bail, Context};
+use serde_json::{Map, Value};
+use crate::core::dialog::action::DialogAction;
+use crate::core::story::condition::Condition;
+use crate::core::story::variables::StoryValue;
+use crate::types::{
+    CharacterState, DialogCharacter, DialogChoice, DialogEntry, DialogScene, DialogTransition,
+    JumpTarget, TransitionType,
+};
+
+const MIN_INK_VERSION: u64 = 19;
+const DEFAULT_LANGUAGE: &str = "english";
+const NARRATOR: &str = "narrator";
+const GLOBAL_DECL: &str = "global decl";
+const CHOSEN_PREFIX: &str = "ink_chosen";
+const DEFAULT_TRANSITION_DURATION: f32 = 0.5;
+
+// flag ของ choice point
+const FLAG_HAS_CONDITION: u64 = 0x1;
+const FLAG_HAS_START_CONTENT: u64 = 0x2;
+const FLAG_HAS_CHOICE_ONLY_CONTENT: u64 = 0x4;
+const FLAG_INVISIBLE_DEFAULT: u64 = 0x8;
+const FLAG_ONCE_ONLY: u64 = 0x10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Component {
+    Index(usize),
+    Name(String),
+}
+
+type InkPath = Vec<Component>;
+
+fn path_key(path: &[Component]) -> String {
+    path.iter()
+        .map(|component| match component {
+            Component::Index(index) => index.to_string(),
+            Component::Name(name) => name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// ค่าใน evaluation stack ของ Ink แปลงเป็น expression ของ Helium
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(StoryValue),
+    Variable(String),
+    Condition(String),
+    /// `x + n` จาก `~ x += n`
+    Offset(String, i64),
+    /// return address ของ `{"^->": ...}` ใช้คู่กับ `$r`
+    DivertTarget,
+}
+
+impl Expr {
+    fn to_condition(&self) -> anyhow::Result<String> {
+        match self {
+            Expr::Value(StoryValue::Str(value)) => Ok(format!("\"{}\"", value)),
+            Expr::Value(value) => Ok(value.to_string()),
+            Expr::Variable(name) => Ok(name.clone()),
+            Expr::Condition(condition) => Ok(condition.clone()),
+            _ => bail!("arithmetic inside conditions is not supported"),
+        }
+    }
+
+    fn parse_condition(&self) -> anyhow::Result<Condition> {
+        Ok(Condition::parse(&self.to_condition()?)?)
+    }
+}
+
+/// ตำแหน่งใน entry ที่ต้องเติม stage หลัง compile ครบทุก container
+enum Fixup {
+    AutoProceed(usize),
+    Choice(usize, usize),
+    Jump(usize, usize),
+}
+
+struct InkImporter<'a> {
+    root: &'a Value,
+    custom_actions: &'a HashSet<String>,
+    scene: DialogScene,
+    language: String,
+    cast: Vec<CharacterState>,
+
+    // สถานะของ flow ที่กำลัง compile
+    flow_start: usize,
+    line: String,
+    glue: bool,
+    string_buffer: Option<String>,
+    tag_buffer: Option<String>,
+    stack: Vec<Expr>,
+    pending_return: bool,
+    pending_speaker: Option<String>,
+    pending_background: Option<String>,
+    pending_transition: Option<DialogTransition>,
+    pending_actions: Vec<DialogAction>,
+    pending_choices: Vec<(DialogChoice, InkPath)>,
+    fallback: Option<InkPath>,
+    terminated: bool,
+
+    /// ตำแหน่งใน Ink ที่ยังรอ entry ถัดไป
+    unbound: Vec<String>,
+    positions: HashMap<String, usize>,
+    fixups: Vec<(Fixup, InkPath)>,
+    queue: VecDeque<InkPath>,
+    once_only: HashMap<String, String>,
+    /// cast และภาษา ณ จุดแรกที่กระโดดไปยังแต่ละตำแหน่ง ใช้เป็นสถานะเริ่มต้นของ flow นั้น
+    /// (flow ถูก compile ตามลำดับใน container ไม่ใช่ลำดับที่เล่นจริง)
+    flow_states: HashMap<String, (Vec<CharacterState>, String)>,
+}
+
+/// แปลง Ink JSON ที่ compile แล้วเป็น `DialogScene` ที่ resolve target เป็น index แล้ว
+pub fn import_ink(source: &[u8], custom_actions: &HashSet<String>) -> anyhow::Result<DialogScene> {
+    // inklecate เขียน UTF-8 BOM นำหน้าไฟล์
+    let source = std::str::from_utf8(source)?.trim_start_matches('\u{feff}');
+    let story: Value = serde_json::from_str(source)?;
+
+    let version = story
+        .get("inkVersion")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("missing `inkVersion`, expected a compiled Ink story"))?;
+    if version < MIN_INK_VERSION {
+        bail!("Ink format version {} is not supported (need {} or newer)", version, MIN_INK_VERSION);
+    }
+    let root = story.get("root").ok_or_else(|| anyhow!("missing `root` container"))?;
+
+    let mut importer = InkImporter {
+        root,
+        custom_actions,
+        scene: DialogScene {
+            characters: Vec::new(),
+            entries: Vec::new(),
+            default_background: String::new(),
+        },
+        language: DEFAULT_LANGUAGE.to_string(),
+        cast: Vec::new(),
+        flow_start: 0,
+        line: String::new(),
+        glue: false,
+        string_buffer: None,
+        tag_buffer: None,
+        stack: Vec::new(),
+        pending_return: false,
+        pending_speaker: None,
+        pending_background: None,
+        pending_transition: None,
+        pending_actions: Vec::new(),
+        pending_choices: Vec::new(),
+        fallback: None,
+        terminated: false,
+        unbound: Vec::new(),
+        positions: HashMap::new(),
+        fixups: Vec::new(),
+        queue: VecDeque::new(),
+        once_only: HashMap::new(),
+        flow_states: HashMap::new(),
+    };
+
+    importer.character(NARRATOR);
+    let globals = importer.read_globals()?;
+    importer.compile_flow(Vec::new(), globals)?;
+    while let Some(path) = importer.next_flow() {
+        importer.compile_flow(path, Vec::new())?;
+    }
+    importer.apply_fixups()?;
+    importer.label_knots()?;
+
+    Ok(importer.scene)
+}
+
+/// content กับ named content ของ container
+type ContainerParts<'v> = (&'v [Value], Option<&'v Map<String, Value>>);
+
+/// แยก container เป็น content กับ named content (element สุดท้ายของ array)
+fn split_container(value: &Value) -> anyhow::Result<ContainerParts<'_>> {
+    let array = value.as_array().ok_or_else(|| anyhow!("expected a container"))?;
+    match array.split_last() {
+        Some((Value::Object(named), content)) => Ok((content, Some(named))),
+        Some((Value::Null, content)) => Ok((content, None)),
+        _ => Ok((array.as_slice(), None)),
+    }
+}
+
+fn container_name(value: &Value) -> Option<&str> {
+    let (_, named) = split_container(value).ok()?;
+    named?.get("#n")?.as_str()
+}
+
+/// ชื่อภายในที่ inklecate สร้างเอง (choice, gather, start content, branch)
+fn is_internal_name(name: &str) -> bool {
+    name.starts_with('#')
+        || name.starts_with('$')
+        || name.starts_with("c-")
+        || name.starts_with("g-")
+        || name == "s"
+        || name == "b"
+}
+
+impl<'a> InkImporter<'a> {
+    fn read_globals(&self) -> anyhow::Result<Vec<DialogAction>> {
+        let Some(decl) = split_container(self.root)?
+            .1
+            .and_then(|named| named.get(GLOBAL_DECL))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut actions = Vec::new();
+        let mut stack = Vec::new();
+        let mut string_buffer: Option<String> = None;
+        for element in split_container(decl)?.0 {
+            match element {
+                Value::String(s) if s == "str" => string_buffer = Some(String::new()),
+                Value::String(s) if s == "/str" => {
+                    stack.push(StoryValue::Str(string_buffer.take().unwrap_or_default()));
+                }
+                Value::String(s) if s.starts_with('^') => {
+                    if let Some(buffer) = string_buffer.as_mut() {
+                        buffer.push_str(&s[1..]);
+                    }
+                }
+                Value::Number(number) => {
+                    let value = number
+                        .as_i64()
+                        .ok_or_else(|| anyhow!("global variable value `{}` is not an integer", number))?;
+                    stack.push(StoryValue::Int(value));
+                }
+                Value::Bool(value) => stack.push(StoryValue::Bool(*value)),
+                Value::Object(map) if map.contains_key("VAR=") => {
+                    let name = map["VAR="].as_str().unwrap_or_default().to_string();
+                    let value = stack
+                        .pop()
+                        .ok_or_else(|| anyhow!("global variable `{}` has an unsupported value", name))?;
+                    actions.push(DialogAction::SetVar { name, value });
+                }
+                Value::Object(map) if map.contains_key("list") => bail!("Ink lists are not supported"),
+                _ => {}
+            }
+        }
+        Ok(actions)
+    }
+
+    fn container_at(&self, path: &[Component]) -> anyhow::Result<&'a Value> {
+        let mut container = self.root;
+        for component in path {
+            let (content, named) = split_container(container)?;
+            container = match component {
+                Component::Index(index) => content.get(*index),
+                Component::Name(name) => named.and_then(|named| named.get(name)),
+            }
+            .ok_or_else(|| anyhow!("Ink path `{}` does not exist", path_key(path)))?;
+        }
+        Ok(container)
+    }
+
+    /// แปลง path ของ divert (absolute หรือ relative `.^.b`) เป็น path ที่ใช้ index ของ container
+    fn resolve(&self, from: &[Component], target: &str) -> anyhow::Result<InkPath> {
+        let mut raw: InkPath = Vec::new();
+        let components: Vec<&str> = match target.strip_prefix('.') {
+            Some(relative) => {
+                raw = from.to_vec();
+                // `^` ตัวแรกคือ container ที่ divert อยู่ (`from`) เอง
+                relative.split('.').skip(1).collect()
+            }
+            None => target.split('.').collect(),
+        };
+
+        for component in components {
+            match component {
+                "^" => {
+                    raw.pop();
+                }
+                _ => raw.push(match component.parse::<usize>() {
+                    Ok(index) => Component::Index(index),
+                    Err(_) => Component::Name(component.to_string()),
+                }),
+            }
+        }
+
+        let mut resolved = Vec::with_capacity(raw.len());
+        let mut container = self.root;
+        for component in raw {
+            let (content, named) = split_container(container)
+                .with_context(|| format!("divert target `{}` goes through a non-container", target))?;
+            let (component, next) = match component {
+                Component::Index(index) => (Component::Index(index), content.get(index)),
+                Component::Name(name) => match named.and_then(|named| named.get(&name)) {
+                    Some(child) => (Component::Name(name), Some(child)),
+                    None => match content.iter().position(|child| container_name(child) == Some(&name)) {
+                        Some(index) => (Component::Index(index), content.get(index)),
+                        None => (Component::Name(name), None),
+                    },
+                },
+            };
+            container = next.ok_or_else(|| anyhow!("divert target `{}` does not exist", target))?;
+            resolved.push(component);
+        }
+        Ok(resolved)
+    }
+
+    /// flow ที่มีจุดกระโดดมาถึงแล้ว (รู้ cast/ภาษาเริ่มต้น) ได้ compile ก่อน ที่เหลือเรียงตามลำดับใน container
+    fn next_flow(&mut self) -> Option<InkPath> {
+        let index = self
+            .queue
+            .iter()
+            .position(|path| self.flow_states.contains_key(&path_key(path)))
+            .unwrap_or(0);
+        self.queue.remove(index)
+    }
+
+    fn compile_flow(&mut self, path: InkPath, mut prelude: Vec<DialogAction>) -> anyhow::Result<()> {
+        self.flow_start = self.scene.entries.len();
+        self.line.clear();
+        self.glue = false;
+        self.string_buffer = None;
+        self.tag_buffer = None;
+        self.stack.clear();
+        self.pending_return = false;
+        self.pending_choices.clear();
+        self.fallback = None;
+        self.terminated = false;
+        self.pending_speaker = None;
+        self.pending_background = None;
+        self.pending_transition = None;
+        self.pending_actions.clear();
+        (self.cast, self.language) = self
+            .flow_states
+            .get(&path_key(&path))
+            .cloned()
+            .unwrap_or_else(|| (Vec::new(), DEFAULT_LANGUAGE.to_string()));
+
+        self.unbound.push(path_key(&path));
+        if let Some(variable) = self.once_only.get(&path_key(&path)) {
+            prelude.push(DialogAction::SetVar {
+                name: variable.clone(),
+                value: StoryValue::Bool(true),
+            });
+        }
+        if !prelude.is_empty() {
+            self.emit(control_entry(prelude));
+        }
+
+        let container = self.container_at(&path)?;
+        self.compile_content(&path, container)?;
+        self.finish_flow()
+    }
+
+    fn compile_content(&mut self, path: &[Component], container: &Value) -> anyhow::Result<()> {
+        let (content, named) = split_container(container)?;
+
+        if let Some(named) = named {
+            for (name, child) in named {
+                // start content ของ choice (`s`) ถูกเรียกแบบ inline เท่านั้น
+                let inline_only = name == "s" && path.len() > 1;
+                if name.starts_with('#') || name == GLOBAL_DECL || inline_only || !child.is_array() {
+                    continue;
+                }
+                let mut child_path = path.to_vec();
+                child_path.push(Component::Name(name.clone()));
+                self.queue.push_back(child_path);
+            }
+        }
+
+        for (index, element) in content.iter().enumerate() {
+            let mut element_path = path.to_vec();
+            element_path.push(Component::Index(index));
+            self.unbound.push(path_key(&element_path));
+
+            if element.is_array() {
+                self.compile_content(&element_path, element)?;
+            } else {
+                self.compile_element(path, element)
+                    .with_context(|| format!("Ink path `{}`", path_key(&element_path)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn compile_element(&mut self, path: &[Component], element: &Value) -> anyhow::Result<()> {
+        match element {
+            Value::Null => {}
+            Value::String(command) => self.compile_command(command)?,
+            Value::Number(number) => {
+                let value = number
+                    .as_i64()
+                    .ok_or_else(|| anyhow!("non-integer number `{}` is not supported", number))?;
+                self.stack.push(Expr::Value(StoryValue::Int(value)));
+            }
+            Value::Bool(value) => self.stack.push(Expr::Value(StoryValue::Bool(*value))),
+            Value::Object(map) => self.compile_object(path, map)?,
+            Value::Array(_) => unreachable!("nested containers are compiled by compile_content"),
+        }
+        Ok(())
+    }
+
+    fn compile_command(&mut self, command: &str) -> anyhow::Result<()> {
+        if let Some(text) = command.strip_prefix('^') {
+            self.push_text(text);
+            return Ok(());
+        }
+
+        match command {
+            "\n" => self.newline(),
+            "ev" | "/ev" | "nop" | "void" => {}
+            "str" => self.string_buffer = Some(String::new()),
+            "/str" => {
+                let text = self.string_buffer.take().unwrap_or_default();
+                self.stack.push(Expr::Value(StoryValue::Str(text)));
+            }
+            "#" => self.tag_buffer = Some(String::new()),
+            "/#" => {
+                let tag = self.tag_buffer.take().unwrap_or_default();
+                // tag ของ choice อยู่ในข้อความตัวเลือก ไม่มีผลกับ entry
+                if self.string_buffer.is_none() {
+                    self.handle_tag(&tag)?;
+                }
+            }
+            "<>" => self.glue = true,
+            "done" => {
+                if self.pending_choices.is_empty() && self.fallback.is_none() {
+                    self.end_story();
+                } else {
+                    self.present_choices();
+                }
+            }
+            "end" => self.end_story(),
+            "pop" => {
+                self.stack.pop();
+            }
+            "du" => {
+                let top = self.stack.last().cloned().ok_or_else(|| anyhow!("evaluation stack is empty"))?;
+                self.stack.push(top);
+            }
+            "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" | "+" | "-" => self.binary(command)?,
+            "!" => {
+                let value = self.pop()?;
+                self.stack.push(Expr::Condition(format!("!({})", value.to_condition()?)));
+            }
+            "_" => match self.pop()? {
+                Expr::Value(StoryValue::Int(value)) => self.stack.push(Expr::Value(StoryValue::Int(-value))),
+                _ => bail!("negating a variable is not supported"),
+            },
+            // `{x}` ในข้อความ แสดงผลด้วย placeholder `{var:x}` ตอนเล่น
+            "out" => match self.pop()? {
+                Expr::Variable(name) => self.push_text(&format!("{{var:{}}}", name)),
+                Expr::Value(value) => self.push_text(&value.to_string()),
+                _ => bail!("printing expressions into text is not supported"),
+            },
+            _ => bail!("unsupported Ink command `{}`", command),
+        }
+        Ok(())
+    }
+
+    fn push_text(&mut self, text: &str) {
+        match (self.tag_buffer.as_mut(), self.string_buffer.as_mut()) {
+            (Some(tag), _) => tag.push_str(text),
+            (None, Some(buffer)) => buffer.push_str(text),
+            (None, None) => self.line.push_str(text),
+        }
+    }
+
+    fn compile_object(&mut self, path: &[Component], map: &Map<String, Value>) -> anyhow::Result<()> {
+        let string_field = |key: &str| map.get(key).and_then(Value::as_str);
+
+        if let Some(target) = string_field("->") {
+            if map.get("var").and_then(Value::as_bool) == Some(true) {
+                bail!("diverting to a variable is not supported");
+            }
+            let target = self.resolve(path, target)?;
+
+            if map.get("c").and_then(Value::as_bool) == Some(true) {
+                let condition = self.pop()?.parse_condition()?;
+                if !self.line.trim().is_empty() {
+                    bail!("conditional text inside a line is not supported");
+                }
+                let entry = self.emit(control_entry(vec![DialogAction::Jump {
+                    target: JumpTarget::Stage(0),
+                    condition: Some(condition),
+                }]));
+                self.link(Fixup::Jump(entry, 0), target);
+            } else if self.pending_return {
+                // เรียก start content ของ choice แล้วกลับมาที่เดิม
+                self.pending_return = false;
+                self.inline_call(&target)?;
+            } else {
+                if !self.pending_choices.is_empty() {
+                    bail!("diverting after choices is not supported");
+                }
+                self.newline();
+                let mut entry = control_entry(Vec::new());
+                entry.auto_proceed = Some(JumpTarget::Stage(0));
+                let entry = self.emit(entry);
+                self.link(Fixup::AutoProceed(entry), target);
+                self.terminated = true;
+            }
+        } else if map.contains_key("^->") {
+            self.stack.push(Expr::DivertTarget);
+        } else if let Some(name) = string_field("temp=") {
+            if name == "$r" {
+                self.pop()?;
+                self.pending_return = true;
+            } else {
+                self.assign(name)?;
+            }
+        } else if let Some(name) = string_field("VAR=") {
+            self.assign(name)?;
+        } else if let Some(name) = string_field("VAR?") {
+            self.stack.push(Expr::Variable(name.to_string()));
+        } else if let Some(target) = string_field("*") {
+            let flags = map.get("flg").and_then(Value::as_u64).unwrap_or(0);
+            let target = self.resolve(path, target)?;
+            self.choice_point(target, flags)?;
+        } else if let Some(tag) = string_field("#") {
+            self.handle_tag(tag)?;
+        } else if map.contains_key("CNT?") {
+            bail!("read counts are not supported");
+        } else if map.contains_key("f()") || map.contains_key("x()") {
+            bail!("functions are not supported");
+        } else if map.contains_key("->t->") {
+            bail!("tunnels are not supported");
+        } else if map.contains_key("<-") {
+            bail!("threads are not supported");
+        } else if map.contains_key("list") {
+            bail!("Ink lists are not supported");
+        } else {
+            bail!("unsupported Ink object `{}`", Value::Object(map.clone()));
+        }
+        Ok(())
+    }
+
+    fn inline_call(&mut self, target: &[Component]) -> anyhow::Result<()> {
+        let container = self.container_at(target)?;
+        for (index, element) in split_container(container)?.0.iter().enumerate() {
+            let is_return = element.get("->").and_then(Value::as_str) == Some("$r")
+                && element.get("var").and_then(Value::as_bool) == Some(true);
+            if is_return {
+                break;
+            }
+
+            let mut element_path = target.to_vec();
+            element_path.push(Component::Index(index));
+            if element.is_array() {
+                self.compile_content(&element_path, element)?;
+            } else {
+                self.compile_element(target, element)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn choice_point(&mut self, target: InkPath, flags: u64) -> anyhow::Result<()> {
+        let condition = match flags & FLAG_HAS_CONDITION {
+            0 => None,
+            _ => Some(self.pop()?.parse_condition()?),
+        };
+        let choice_only = match flags & FLAG_HAS_CHOICE_ONLY_CONTENT {
+            0 => String::new(),
+            _ => self.pop_string()?,
+        };
+        let start = match flags & FLAG_HAS_START_CONTENT {
+            0 => String::new(),
+            _ => self.pop_string()?,
+        };
+
+        if flags & FLAG_INVISIBLE_DEFAULT != 0 {
+            self.fallback = Some(target);
+            return Ok(());
+        }
+
+        let mut conditions: Vec<Condition> = condition.into_iter().collect();
+        if flags & FLAG_ONCE_ONLY != 0 {
+            let key = path_key(&target);
+            let variable = format!(
+                "{}.{}",
+                CHOSEN_PREFIX,
+                key.replace(|c: char| !c.is_alphanumeric(), "_")
+            );
+            conditions.push(Condition::parse(&format!("!{}", variable))?);
+            self.once_only.insert(key, variable);
+        }
+
+        let text = format!("{}{}", start, choice_only).trim().to_string();
+        let choice = DialogChoice {
+            text: HashMap::from([(self.language.clone(), text)]),
+            target: JumpTarget::Stage(0),
+            conditions,
+            show_locked: false,
+        };
+        self.pending_choices.push((choice, target));
+        Ok(())
+    }
+
+    fn assign(&mut self, name: &str) -> anyhow::Result<()> {
+        let action = match self.pop()? {
+            Expr::Value(value) => DialogAction::SetVar {
+                name: name.to_string(),
+                value,
+            },
+            Expr::Offset(variable, amount) if variable == name => DialogAction::AddVar {
+                name: name.to_string(),
+                amount,
+            },
+            _ => bail!("only `{} = <value>` and `{} += <int>` assignments are supported", name, name),
+        };
+        self.emit(control_entry(vec![action]));
+        Ok(())
+    }
+
+    fn binary(&mut self, op: &str) -> anyhow::Result<()> {
+        let right = self.pop()?;
+        let left = self.pop()?;
+
+        let result = match (op, left, right) {
+            ("+" | "-", Expr::Variable(name), Expr::Value(StoryValue::Int(amount))) => {
+                Expr::Offset(name, if op == "-" { -amount } else { amount })
+            }
+            ("+", Expr::Value(StoryValue::Int(l)), Expr::Value(StoryValue::Int(r))) => {
+                Expr::Value(StoryValue::Int(l + r))
+            }
+            ("-", Expr::Value(StoryValue::Int(l)), Expr::Value(StoryValue::Int(r))) => {
+                Expr::Value(StoryValue::Int(l - r))
+            }
+            ("+" | "-", _, _) => bail!("only `variable {} <int>` arithmetic is supported", op),
+            (_, left, right) => Expr::Condition(format!(
+                "({} {} {})",
+                left.to_condition()?,
+                op,
+                right.to_condition()?
+            )),
+        };
+        self.stack.push(result);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> anyhow::Result<Expr> {
+        self.stack.pop().ok_or_else(|| anyhow!("evaluation stack is empty"))
+    }
+
+    fn pop_string(&mut self) -> anyhow::Result<String> {
+        match self.pop()? {
+            Expr::Value(StoryValue::Str(text)) => Ok(text),
+            other => bail!("expected choice text, got {:?}", other),
+        }
+    }
+
+    fn handle_tag(&mut self, tag: &str) -> anyhow::Result<()> {
+        let tag = tag.trim();
+        let (key, value) = match tag.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (tag, ""),
+        };
+        let words: Vec<&str> = value.split_whitespace().collect();
+
+        match key {
+            "speaker" => {
+                self.character(value);
+                self.pending_speaker = Some(value.to_string());
+            }
+            "bg" | "background" => self.pending_background = Some(value.to_string()),
+            "language" => self.language = value.to_string(),
+            "show" => {
+                let [name, rest @ ..] = words.as_slice() else {
+                    bail!("`# show:` expects `<character> [position] [expression]`");
+                };
+                self.character(name);
+                let state = CharacterState {
+                    name: name.to_string(),
+                    position: rest.first().copied().unwrap_or("center").to_string(),
+                    expression: rest.get(1).copied().unwrap_or("default").to_string(),
+                    highlight: rest.get(2) == Some(&"highlight"),
+                };
+                match self.cast.iter_mut().find(|c| c.name == *name) {
+                    Some(existing) => *existing = state,
+                    None => self.cast.push(state),
+                }
+            }
+            "hide" => self.cast.retain(|c| c.name != value),
+            "transition" => {
+                let [kind, rest @ ..] = words.as_slice() else {
+                    bail!("`# transition:` expects a type");
+                };
+                let type_name: TransitionType = serde_json::from_value(Value::String(kind.to_string()))
+                    .map_err(|_| anyhow!("unknown transition `{}`", kind))?;
+                let duration = match rest.first() {
+                    Some(raw) => raw.parse::<f32>().map_err(|_| anyhow!("invalid duration `{}`", raw))?,
+                    None => DEFAULT_TRANSITION_DURATION,
+                };
+                self.pending_transition = Some(DialogTransition { type_name, duration });
+            }
+            "character" => self.declare_character(value)?,
+            _ => {
+                let source = if value.is_empty() { key.to_string() } else { format!("{}:{}", key, value) };
+                // tag อื่นเป็น metadata ของ Ink ยกเว้นที่ตรงกับ action ที่รู้จัก
+                match DialogAction::parse(&source) {
+                    Ok(DialogAction::Custom { name, .. }) if !self.custom_actions.contains(&name) => {}
+                    Ok(action) => self.pending_actions.push(action),
+                    Err(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `miki "Miki" sprite=characters/miki.png`
+    fn declare_character(&mut self, value: &str) -> anyhow::Result<()> {
+        let (name, mut rest) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+        if name.is_empty() {
+            bail!("`# character:` expects a name");
+        }
+
+        rest = rest.trim_start();
+        let mut display_name = None;
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| anyhow!("unterminated display name"))?;
+            display_name = Some(quoted[..end].to_string());
+            rest = &quoted[end + 1..];
+        }
+
+        let language = self.language.clone();
+        let character = self.character(name);
+        if let Some(display_name) = display_name {
+            character.display_name.insert(language, display_name);
+        }
+        for option in rest.split_whitespace() {
+            match option.split_once('=') {
+                Some(("sprite", sprite)) => character.sprite = sprite.to_string(),
+                _ => bail!("unknown character option `{}`", option),
+            }
+        }
+        Ok(())
+    }
+
+    fn character(&mut self, name: &str) -> &mut DialogCharacter {
+        let index = match self.scene.characters.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.scene.characters.push(DialogCharacter {
+                    name: name.to_string(),
+                    display_name: HashMap::new(),
+                    sprite: String::new(),
+                    positions: HashMap::new(),
+                });
+                self.scene.characters.len() - 1
+            }
+        };
+        &mut self.scene.characters[index]
+    }
+
+    /// จบบรรทัด: ข้อความที่สะสมไว้กลายเป็น entry ใหม่
+    fn newline(&mut self) {
+        if std::mem::take(&mut self.glue) {
+            return;
+        }
+        let text = std::mem::take(&mut self.line).trim().to_string();
+        if !text.is_empty() {
+            let entry = self.line_entry(text);
+            self.emit(entry);
+        }
+    }
+
+    fn line_entry(&mut self, text: String) -> DialogEntry {
+        DialogEntry {
+            label: None,
+            character: self.pending_speaker.take().unwrap_or_else(|| NARRATOR.to_string()),
+            text: HashMap::from([(self.language.clone(), text)]),
+            actions: std::mem::take(&mut self.pending_actions),
+            choices: Vec::new(),
+            auto_proceed: None,
+            character_states: self.cast.clone(),
+            background: self.pending_background.take(),
+            transition: self.pending_transition.take(),
+        }
+    }
+
+    fn emit(&mut self, entry: DialogEntry) -> usize {
+        let index = self.scene.entries.len();
+        for key in self.unbound.drain(..) {
+            self.positions.entry(key).or_insert(index);
+        }
+        self.scene.entries.push(entry);
+        self.terminated = false;
+        index
+    }
+
+    fn end_story(&mut self) {
+        self.newline();
+        self.pending_choices.clear();
+        self.fallback = None;
+        self.emit(control_entry(vec![DialogAction::End]));
+        self.terminated = true;
+    }
+
+    /// ผูกตัวเลือกกับบรรทัดล่าสุดของ flow นี้ (หรือ entry ว่างถ้าไม่มี)
+    fn present_choices(&mut self) {
+        self.newline();
+
+        let attach_to = self
+            .scene
+            .entries
+            .len()
+            .checked_sub(1)
+            .filter(|index| *index >= self.flow_start)
+            .filter(|index| {
+                let entry = &self.scene.entries[*index];
+                !entry.is_control() && entry.actions.is_empty() && entry.auto_proceed.is_none()
+            });
+
+        let entry_index = match attach_to {
+            Some(index) => {
+                for key in self.unbound.drain(..) {
+                    self.positions.entry(key).or_insert(index);
+                }
+                index
+            }
+            None => {
+                let entry = self.line_entry(String::new());
+                self.emit(entry)
+            }
+        };
+
+        for (choice, target) in std::mem::take(&mut self.pending_choices) {
+            let choices = &mut self.scene.entries[entry_index].choices;
+            choices.push(choice);
+            let choice_index = choices.len() - 1;
+            self.link(Fixup::Choice(entry_index, choice_index), target);
+        }
+
+        match self.fallback.take() {
+            Some(target) => {
+                self.scene.entries[entry_index].auto_proceed = Some(JumpTarget::Stage(0));
+                self.link(Fixup::AutoProceed(entry_index), target);
+            }
+            // ไม่มีตัวเลือกที่เลือกได้และไม่มี fallback = เรื่องจบ (เหมือน Ink)
+            None => {
+                self.emit(control_entry(vec![DialogAction::End]));
+            }
+        }
+        self.terminated = true;
+    }
+
+    fn finish_flow(&mut self) -> anyhow::Result<()> {
+        if self.string_buffer.is_some() || self.tag_buffer.is_some() {
+            bail!("unterminated string or tag");
+        }
+        self.newline();
+
+        if !self.pending_choices.is_empty() || self.fallback.is_some() {
+            self.present_choices();
+        } else if !self.terminated || !self.unbound.is_empty() {
+            self.end_story();
+        }
+        Ok(())
+    }
+
+    /// ให้ `fixup` ชี้ไปยัง `target` และจำ cast/ภาษาตอนนี้ไว้เป็นสถานะเริ่มต้นของ flow ที่ `target`
+    fn link(&mut self, fixup: Fixup, target: InkPath) {
+        let state = (self.cast.clone(), self.language.clone());
+        self.flow_states.entry(path_key(&target)).or_insert(state);
+        self.fixups.push((fixup, target));
+    }
+
+    fn apply_fixups(&mut self) -> anyhow::Result<()> {
+        for (fixup, target) in std::mem::take(&mut self.fixups) {
+            let key = path_key(&target);
+            let stage = *self
+                .positions
+                .get(&key)
+                .ok_or_else(|| anyhow!("divert target `{}` is not reachable content", key))?;
+            let target = JumpTarget::Stage(stage);
+
+            match fixup {
+                Fixup::AutoProceed(entry) => self.scene.entries[entry].auto_proceed = Some(target),
+                Fixup::Choice(entry, choice) => self.scene.entries[entry].choices[choice].target = target,
+                Fixup::Jump(entry, action) => {
+                    if let DialogAction::Jump { target: jump, .. } = &mut self.scene.entries[entry].actions[action] {
+                        *jump = target;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// ตั้ง label ให้ entry แรกของทุก knot และ stitch
+    fn label_knots(&mut self) -> anyhow::Result<()> {
+        let Some(knots) = split_container(self.root)?.1 else {
+            return Ok(());
+        };
+
+        let mut labels = Vec::new();
+        for (knot, container) in knots {
+            if is_internal_name(knot) || knot == GLOBAL_DECL || !container.is_array() {
+                continue;
+            }
+            labels.push((knot.clone(), knot.clone()));
+
+            let Some(stitches) = split_container(container)?.1 else {
+                continue;
+            };
+            for (stitch, child) in stitches {
+                if !is_internal_name(stitch) && child.is_array() {
+                    labels.push((format!("{}.{}", knot, stitch), format!("{}.{}", knot, stitch)));
+                }
+            }
+        }
+
+        for (label, key) in labels {
+            if let Some(index) = self.positions.get(&key) {
+                self.scene.entries[*index].label.get_or_insert(label);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn control_entry(actions: Vec<DialogAction>) -> DialogEntry {
+    DialogEntry {
+        label: None,
+        character: String::new(),
+        text: HashMap::new(),
+        actions,
+        choices: Vec::new(),
+        auto_proceed: None,
+        character_states: Vec::new(),
+        background: None,
+        transition: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(source: &str) -> anyhow::Result<DialogScene> {
+        import_ink(source.as_bytes(), &HashSet::new())
+    }
+
+    fn error(source: &str) -> String {
+        format!("{:#}", import(source).unwrap_err())
+    }
+
+    #[test]
+    fn imports_sample_story() {
+        let scene = import(include_str!("../../../assets/dialogs/stairs.ink.json")).unwrap();
+        assert_eq!(scene.entries[0].actions, vec![DialogAction::SetVar { name: "affection".to_string(), value: StoryValue::Int(0) }]);
+
+        let start = scene.label_index("rooftop_talk").unwrap();
+        assert_eq!(scene.entries[start].text["english"], "Miki waves at you from the stairs.");
+        assert_eq!(scene.entries[start].background.as_deref(), Some("backgrounds/school_hallway.png"));
+
+        let hey = &scene.entries[start + 1];
+        assert_eq!(hey.character, "miki");
+        assert_eq!(hey.text["english"], "Hey! Over here!");
+        assert_eq!(hey.choices.len(), 2);
+        assert_eq!(hey.choices[1].text["english"], "Pretend not to see her");
+        // choice `*` เลือกได้ครั้งเดียว
+        assert_eq!(
+            hey.choices[0].conditions,
+            vec![Condition::parse("!ink_chosen.rooftop_talk_0_c_0").unwrap()]
+        );
+
+        let ending = scene.label_index("ending").unwrap();
+        assert_eq!(
+            scene.entries[ending].actions,
+            vec![DialogAction::Jump {
+                target: JumpTarget::Stage(ending + 2),
+                condition: Some(Condition::parse("affection > 0").unwrap()),
+            }]
+        );
+        // ตัวละครที่ `# show` ไว้ก่อน divert ยังอยู่บนจอใน knot ปลายทาง
+        let good = &scene.entries[ending + 2];
+        assert_eq!(good.character, "player");
+        assert_eq!(good.character_states.iter().map(|state| state.name.as_str()).collect::<Vec<_>>(), ["miki"]);
+    }
+
+    #[test]
+    fn errors_name_the_ink_path() {
+        assert_eq!(error(r#"{"root":[]}"#), "missing `inkVersion`, expected a compiled Ink story");
+        assert_eq!(
+            error(r#"{"inkVersion":21,"root":[["^Hi","\n",{"f()":"greet"},"done",null],"done",null]}"#),
+            "Ink path `0.2`: functions are not supported"
+        );
+        assert_eq!(
+            error(r#"{"inkVersion":21,"root":[["^Hi","\n",{"->":"nowhere"},"done",null],"done",null]}"#),
+            "Ink path `0.2`: divert target `nowhere` does not exist"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! แทนค่า placeholder ในข้อความตอนแสดงผล
+//!
+//! - `{player_name}` / `{var:gold}` - ค่าของ story variable
+//! - `{char:miki}` - ชื่อที่แสดงของตัวละครในภาษาปัจจุบัน
+//!
+//! ถ้าไม่พบค่า: debug build แสดง `[?var:gold]` ให้เห็นชัด ส่วน release แสดงเป็นข้อความว่าง
+//! (ตัวละครใช้ชื่อภายในแทน) วงเล็บปีกกาที่ไม่ใช่รูปแบบข้างบน และ tag ของ typewriter
+//! (`{w=0.5}`, `{p}`, `{nw}`) จะคงไว้ตามเดิม
+
+use bevy::prelude::*;
+use crate::core::story::variables::StoryVariables;
+use crate::types::DialogCharacter;
+
+/// แทนค่า placeholder ทั้งหมดใน `text`
+pub fn interpolate(
+    text: &str,
+    variables: &StoryVariables,
+    characters: &[DialogCharacter],
+    language: &str,
+) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        let tail = &rest[start..];
+        let Some(end) = tail.find('}') else {
+            break;
+        };
+
+        result.push_str(&rest[..start]);
+        let placeholder = &tail[1..end];
+        match resolve(placeholder, variables, characters, language) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// ชื่อที่แสดงของตัวละคร (display name เองก็ใช้ `{var:...}` ได้ เช่นชื่อผู้เล่น)
+pub fn character_name(
+    name: &str,
+    variables: &StoryVariables,
+    characters: &[DialogCharacter],
+    language: &str,
+) -> Option<String> {
+    let display_name = characters
+        .iter()
+        .find(|character| character.name == name)?
+        .display_name
+        .get(language)?;
+    Some(interpolate(display_name, variables, &[], language))
+}
+
+/// `None` = ไม่ใช่ placeholder ให้คงข้อความเดิมไว้
+fn resolve(
+    placeholder: &str,
+    variables: &StoryVariables,
+    characters: &[DialogCharacter],
+    language: &str,
+) -> Option<String> {
+    let (kind, name) = placeholder.split_once(':').unwrap_or(("var", placeholder));
+    // `{p}` / `{nw}` เป็น tag ของ typewriter
+    if !is_name(name) || matches!(placeholder, "p" | "nw") {
+        return None;
+    }
+
+    match kind {
+        "var" => Some(match variables.get(name) {
+            Some(value) => value.to_string(),
+            None => missing(placeholder, String::new()),
+        }),
+        "char" => Some(
+            character_name(name, variables, characters, language)
+                .unwrap_or_else(|| missing(placeholder, name.to_string())),
+        ),
+        _ => None,
+    }
+}
+
+fn missing(placeholder: &str, fallback: String) -> String {
+    warn!("No value for placeholder `{{{}}}`", placeholder);
+    if cfg!(debug_assertions) {
+        format!("[?{}]", placeholder)
+    } else {
+        fallback
+    }
+}
+
+/// ชื่อตัวแปรหรือตัวละคร (`ink_chosen.knot.0` ก็นับ)
+fn is_name(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
@@ -1,25 +1,117 @@
 use bevy::prelude::*;
+use bevy::asset::LoadState;
 use crate::core::resources::{DialogResource, VNState, DialogManager};
 use crate::core::text::styles::TextStyleResource;
 use crate::core::text::components::TextStylePreset;
 use crate::util::identifier::dialog;
 use crate::types::DialogScene;
+use crate::core::story::manifest::{StoryManifest, StoryResource, STORY_MANIFEST};
+use crate::core::story::variables::StoryVariables;
+use crate::core::dialog::interpolate::{character_name, interpolate};
 
-pub fn load_dialogs(
+/// เริ่มโหลด `story.json` ตั้งแต่เปิดโปรแกรม
+pub fn load_story_manifest(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(StoryResource {
+        manifest: asset_server.load::<StoryManifest>(STORY_MANIFEST),
+        preloaded: Vec::new(),
+    });
+}
+
+/// ลงทะเบียน scene ตาม manifest เมื่อโหลดเสร็จ และทุกครั้งที่ `story.json` ถูกแก้
+/// (เพิ่มหรือลบ scene ได้โดยไม่ต้อง build ใหม่ scene ที่ถูกลบออกจาก manifest จะหายไปด้วย)
+pub fn register_story_scenes(
+    mut manifest_events: EventReader<AssetEvent<StoryManifest>>,
     asset_server: Res<AssetServer>,
+    story: Res<StoryResource>,
+    manifests: Res<Assets<StoryManifest>>,
     mut dialog_resource: ResMut<DialogResource>,
     mut vn_state: ResMut<VNState>,
 ) {
-    let intro_scene = dialog("intro.dialog.json").load::<DialogScene>(&asset_server);
-    let school_scene = dialog("school.dialog.json").load::<DialogScene>(&asset_server);
-    let choices_scene = dialog("choices.dialog.json").load::<DialogScene>(&asset_server);
+    for event in manifest_events.read() {
+        if !event.is_loaded_with_dependencies(&story.manifest) && !event.is_modified(&story.manifest) {
+            continue;
+        }
+        let Some(manifest) = manifests.get(&story.manifest) else {
+            continue;
+        };
+
+        dialog_resource.scenes = manifest
+            .scenes
+            .iter()
+            .map(|scene| (scene.name.clone(), dialog(&scene.path).load::<DialogScene>(&asset_server)))
+            .collect();
+        info!("ลงทะเบียน {} scene จาก {}", manifest.scenes.len(), STORY_MANIFEST);
+
+        if dialog_resource.current_scene.is_none() {
+            let start = dialog_resource.scenes.get(&manifest.start).cloned();
+            dialog_resource.current_scene = start.clone();
+            vn_state.current_scene = manifest.start.clone();
+            vn_state.current_scene_handle = start;
+        }
+    }
+}
+
+/// ตรวจ `scene#label`, `change_scene` และ `call_scene` ข้าม scene เมื่อ scene ที่ลงทะเบียนโหลดครบ
+/// (ตอนโหลดแต่ละไฟล์ยังไม่มี scene อื่นให้ตรวจ) ตรวจใหม่ทุกครั้งที่ scene หรือ `story.json` ถูกแก้
+pub fn check_scene_links(
+    mut scene_events: EventReader<AssetEvent<DialogScene>>,
+    mut manifest_events: EventReader<AssetEvent<StoryManifest>>,
+    mut pending: Local<bool>,
+    asset_server: Res<AssetServer>,
+    dialog_resource: Res<DialogResource>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+) {
+    if scene_events.read().count() > 0 || manifest_events.read().count() > 0 {
+        *pending = true;
+    }
+    let loading = dialog_resource
+        .scenes
+        .values()
+        .any(|handle| matches!(asset_server.load_state(handle), LoadState::NotLoaded | LoadState::Loading));
+    if !*pending || dialog_resource.scenes.is_empty() || loading {
+        return;
+    }
+    *pending = false;
+
+    let mut names: Vec<&String> = dialog_resource.scenes.keys().collect();
+    names.sort();
+    for name in names {
+        let Some(scene) = dialog_resource.scenes.get(name).and_then(|handle| dialog_scenes.get(handle)) else {
+            continue;
+        };
+        for (index, target, label) in scene.scene_links() {
+            let target_scene = dialog_resource.scenes.get(target).and_then(|handle| dialog_scenes.get(handle));
+            match (target_scene, label) {
+                (None, _) => error!("Scene `{}` entry {}: scene `{}` is not registered or failed to load", name, index, target),
+                (Some(target_scene), Some(label)) if target_scene.label_index(label).is_none() => {
+                    error!("Scene `{}` entry {}: scene `{}` has no label `{}`", name, index, target, label)
+                }
+                _ => {}
+            }
+        }
+    }
+}
 
-    dialog_resource.scenes.insert("intro".to_string(), intro_scene.clone());
-    dialog_resource.scenes.insert("school".to_string(), school_scene);
-    dialog_resource.scenes.insert("choices".to_string(), choices_scene);
+/// โหลด asset ใน `preload` ของ scene ปัจจุบันไว้ล่วงหน้า และปล่อยของ scene ก่อนหน้า
+pub fn preload_scene_assets(
+    asset_server: Res<AssetServer>,
+    mut story: ResMut<StoryResource>,
+    manifests: Res<Assets<StoryManifest>>,
+    vn_state: Res<VNState>,
+    mut preloaded_scene: Local<Option<String>>,
+) {
+    if preloaded_scene.as_deref() == Some(vn_state.current_scene.as_str()) {
+        return;
+    }
+    let Some(manifest) = manifests.get(&story.manifest) else {
+        return;
+    };
 
-    dialog_resource.current_scene = Some(intro_scene.clone());
-    vn_state.current_scene_handle = Some(intro_scene);
+    story.preloaded = manifest
+        .scene(&vn_state.current_scene)
+        .map(|scene| scene.preload.iter().map(|path| asset_server.load_untyped(path).untyped()).collect())
+        .unwrap_or_default();
+    *preloaded_scene = Some(vn_state.current_scene.clone());
 }
 
 pub fn manage_dialog_state(
@@ -27,6 +119,7 @@ pub fn manage_dialog_state(
     mut dialog_manager: ResMut<DialogManager>,
     dialog_resource: Res<DialogResource>,
     dialog_scenes: Res<Assets<DialogScene>>,
+    variables: Res<StoryVariables>,
     text_styles: Res<TextStyleResource>,
     mut character_query: Query<&mut Text, (With<crate::ui::dialog::CharacterName>, Without<crate::core::dialog::typewriter::TypewriterText>)>,
     mut dialog_query: Query<(&mut Text, &mut crate::core::dialog::typewriter::TypewriterText), With<crate::ui::dialog::DialogText>>,
@@ -40,7 +133,6 @@ pub fn manage_dialog_state(
         // Reset dialog text และ typewriter
         if let Ok((mut text, mut typewriter)) = dialog_query.get_single_mut() {
             *typewriter = crate::core::dialog::typewriter::TypewriterText::new("", 0.05);
-            text.sections[0].value = "".to_string();
 
             // Update text style ด้วยภาษาปัจจุบัน
             let current_language = match state.language.as_str() {
@@ -49,7 +141,7 @@ pub fn manage_dialog_state(
                 "japanese" => crate::core::language::types::LanguageCode::Japanese,
                 _ => crate::core::language::types::LanguageCode::Thai,
             };
-            text.sections[0].style = TextStylePreset::DialogText.to_style(&text_styles, &current_language);
+            text.sections = typewriter.sections(&TextStylePreset::DialogText, &text_styles, &current_language);
         }
 
         dialog_manager.reset();
@@ -62,18 +154,14 @@ pub fn manage_dialog_state(
                 if state.stage < scene.entries.len() {
                     let entry = &scene.entries[state.stage];
 
-                    let character_display_name = scene
-                        .characters
-                        .iter()
-                        .find(|c| c.name == entry.character)
-                        .and_then(|c| c.display_name.get(&state.language))
-                        .cloned()
-                        .unwrap_or_else(|| entry.character.clone());
+                    let character_display_name =
+                        character_name(&entry.character, &variables, &scene.characters, &state.language)
+                            .unwrap_or_else(|| entry.character.clone());
 
                     let dialog_text = entry
                         .text
                         .get(&state.language)
-                        .cloned()
+                        .map(|text| interpolate(text, &variables, &scene.characters, &state.language))
                         .unwrap_or_else(|| format!("[No text in {}]", state.language));
 
                     dialog_manager.set_content(character_display_name.clone(), dialog_text.clone());
@@ -95,19 +183,43 @@ pub fn manage_dialog_state(
                     // Update dialog text และ typewriter
                     if let Ok((mut text, mut typewriter)) = dialog_query.get_single_mut() {
                         *typewriter = crate::core::dialog::typewriter::TypewriterText::new(&dialog_text, 0.05);
-                        text.sections[0].value = "".to_string();
 
-                        // Update dialog text style
+                        // Update dialog text style (section ละหนึ่งช่วงของ markup)
                         let current_language = match state.language.as_str() {
                             "thai" => crate::core::language::types::LanguageCode::Thai,
                             "english" => crate::core::language::types::LanguageCode::English,
                             "japanese" => crate::core::language::types::LanguageCode::Japanese,
                             _ => crate::core::language::types::LanguageCode::Thai,
                         };
-                        text.sections[0].style = TextStylePreset::DialogText.to_style(&text_styles, &current_language);
+                        text.sections = typewriter.sections(&TextStylePreset::DialogText, &text_styles, &current_language);
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Hot reload: เมื่อไฟล์ scene ที่กำลังเล่นถูกแก้ ให้แสดง entry ปัจจุบันใหม่จากข้อมูลชุดใหม่
+pub fn reload_modified_scenes(
+    mut asset_events: EventReader<AssetEvent<DialogScene>>,
+    mut state: ResMut<VNState>,
+    mut choice_state: ResMut<crate::core::dialog::choice::ChoiceState>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+) {
+    for event in asset_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        if state.current_scene_handle.as_ref().map(|handle| handle.id()) != Some(*id) {
+            continue;
+        }
+        let Some(scene) = dialog_scenes.get(*id) else {
+            continue;
+        };
+
+        let stage = state.stage.min(scene.entries.len().saturating_sub(1));
+        info!("Reloaded scene '{}', restarting at stage {}", state.current_scene, stage);
+        choice_state.deactivate();
+        state.force_stage(stage);
+    }
+}
@@ -1,3 +1,12 @@
 pub mod manager;
 pub mod typewriter;
-pub mod choice;
\ No newline at end of file
+pub mod choice;
+pub mod action;
+pub mod script;
+pub mod ink;
+pub mod yarn;
+pub mod interpolate;
+pub mod rollback;
+pub mod backlog;
+pub mod skip;
+pub mod auto;
\ No newline at end of file
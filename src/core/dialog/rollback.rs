@@ -0,0 +1,100 @@
+//! ย้อนกลับไปบรรทัดก่อนหน้า (`InputAction::Rollback` หรือเลือกบรรทัดใน backlog)
+//!
+//! ทุกบรรทัดที่แสดงจะถูกบันทึกเป็น `HistoryStep` ใน `DialogHistory` พร้อม scene, ตัวแปร,
+//! call stack และพื้นหลัง การย้อนจึงข้าม `change_scene` ได้ และตัวเลือกที่เลือกหลังจุดนั้นถูกยกเลิก
+
+use bevy::prelude::*;
+use crate::core::dialog::backlog::Backlog;
+use crate::core::dialog::choice::ChoiceState;
+use crate::core::input::action::{ActionState, InputAction};
+use crate::core::resources::{DialogHistory, DialogManager, DialogResource, HistoryStep, VNState};
+use crate::core::save::manager::SceneRestore;
+use crate::core::scene::background::Background;
+use crate::core::scene::transition::TransitionState;
+use crate::core::story::variables::StoryVariables;
+use crate::ui::choice::{ChoiceContainer, ChoiceOverlay};
+
+/// ย้อนไปยังบรรทัดที่ระบุ (scene, stage) ซึ่งต้องยังอยู่ใน `DialogHistory`
+#[derive(Event)]
+pub struct RollbackEvent {
+    pub scene: String,
+    pub stage: usize,
+}
+
+/// บันทึกบรรทัดที่เพิ่งแสดง (ต้องรันหลัง `update_background` เพื่อได้พื้นหลังของ entry นี้)
+pub fn record_history_step(
+    state: Res<VNState>,
+    dialog_manager: Res<DialogManager>,
+    variables: Res<StoryVariables>,
+    choice_state: Res<ChoiceState>,
+    mut history: ResMut<DialogHistory>,
+    mut backlog: ResMut<Backlog>,
+    background_query: Query<&Background>,
+) {
+    // stage ที่เพิ่งเปลี่ยนใน frame นี้ยังไม่ถูกแสดง (พื้นหลังยังเป็นของบรรทัดเดิม)
+    if state.should_reset_dialog()
+        || dialog_manager.is_processing()
+        || history.is_current(&state.current_scene, state.stage)
+    {
+        return;
+    }
+
+    backlog.add_line(&state.current_scene, state.stage, &variables);
+    history.add_step(HistoryStep {
+        scene: state.current_scene.clone(),
+        stage: state.stage,
+        call_stack: state.call_stack.clone(),
+        variables: variables.clone(),
+        choice_count: choice_state.history.len(),
+        background: background_query
+            .get_single()
+            .ok()
+            .map(|background| background.current_path.clone()),
+    });
+}
+
+pub fn handle_rollback(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut rollback_events: EventReader<RollbackEvent>,
+    mut state: ResMut<VNState>,
+    mut dialog_resource: ResMut<DialogResource>,
+    mut history: ResMut<DialogHistory>,
+    mut backlog: ResMut<Backlog>,
+    mut choice_state: ResMut<ChoiceState>,
+    mut variables: ResMut<StoryVariables>,
+    mut transition_state: ResMut<TransitionState>,
+    mut restore: ResMut<SceneRestore>,
+    container_query: Query<Entity, With<ChoiceContainer>>,
+    overlay_query: Query<Entity, With<ChoiceOverlay>>,
+) {
+    let target = rollback_events.read().last().map(|event| (event.scene.clone(), event.stage));
+    let step = match target {
+        Some((scene, stage)) => history.go_back_to(&scene, stage),
+        None if actions.just_pressed(InputAction::Rollback) => history.go_back(),
+        None => return,
+    };
+    let Some(step) = step else {
+        return;
+    };
+
+    if step.scene != state.current_scene && !dialog_resource.change_scene(&step.scene, &mut state) {
+        error!("Cannot roll back to unknown scene `{}`", step.scene);
+        return;
+    }
+    state.force_stage(step.stage);
+    state.call_stack = step.call_stack;
+    // ย้อนกลับไม่นับเป็นการเปลี่ยน scene
+    state.autosave_pending = false;
+    *variables = step.variables;
+    backlog.rewind_to(&step.scene, step.stage);
+
+    choice_state.deactivate();
+    choice_state.history.truncate(step.choice_count);
+    for entity in container_query.iter().chain(overlay_query.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    transition_state.skip_entry(&state.current_scene, state.stage);
+    restore.request(step.background);
+}
@@ -0,0 +1,606 @@
+//! `.hel` screenplay script ที่ compile เป็น `DialogScene`
+//!
+//! ```text
+//! # comment
+//! @language english
+//! @character miki "Miki" sprite=characters/miki.png
+//! @name miki thai มิกิ
+//! @default_bg backgrounds/school_hallway.png
+//!
+//! @label lunch
+//! @bg backgrounds/cafeteria.png
+//! @transition crossfade 0.5
+//! @show miki right happy
+//! The bell rings, signaling the start of lunch break.
+//! [ask] miki: Where would you like to go first?
+//! * [opt_food] The cafeteria -> cafeteria
+//! * The library -> library if affection.miki >= 3
+//! @set_var met_miki=true
+//! @jump secret if affection.miki >= 5
+//! -> school#lunch
+//!
+//! === thai
+//! ask: อยากไปที่ไหนก่อนดี?
+//! opt_food: โรงอาหาร
+//! ```
+//!
+//! - `name: text` คือบทพูด, บรรทัดที่ไม่มีชื่อนำหน้าเป็นของ `narrator`
+//! - `[id]` หน้าบรรทัดหรือหน้าตัวเลือก ใช้อ้างอิงคำแปลใน block `=== <language>`
+//! - `@bg`, `@transition`, `@show`, `@hide`, `@label` มีผลกับบรรทัดถัดไป
+//! - `*` ตัวเลือก, `->` และ `@<action> args` อื่นๆ ผูกกับบรรทัดก่อนหน้า
+//!   (ทำงานเมื่อผู้เล่นกดผ่านบรรทัดนั้น เหมือน `actions` / `auto_proceed` ใน JSON)
+//! - `{var:gold}` / `{char:miki}` ในข้อความถูกแทนค่าตอนแสดงผล (ดู `interpolate`)
+//! - `[b]`, `[i]`, `[color=#f00]` ในข้อความเป็น markup (ดู `text::markup`)
+//! - `{w=0.5}`, `{speed=0.02}` / `{/speed}`, `{p}`, `{nw}` ควบคุมการพิมพ์ (ดู `typewriter`)
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::core::dialog::action::DialogAction;
+use crate::core::story::condition::Condition;
+use crate::types::{
+    CharacterState, DialogCharacter, DialogChoice, DialogEntry, DialogScene, DialogTransition,
+    JumpTarget, TransitionType,
+};
+
+const DEFAULT_LANGUAGE: &str = "english";
+const NARRATOR: &str = "narrator";
+const DEFAULT_TRANSITION_DURATION: f32 = 0.5;
+
+/// Error ของ script พร้อมตำแหน่ง (นับจาก 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// ตำแหน่งของข้อความที่รอคำแปลจาก translation block
+enum TextSlot {
+    Entry(usize),
+    Choice(usize, usize),
+}
+
+/// label ที่ถูกอ้างถึง เก็บตำแหน่งไว้รายงาน error
+struct LabelReference {
+    label: String,
+    line: usize,
+    column: usize,
+}
+
+struct ScriptParser<'a> {
+    custom_actions: &'a HashSet<String>,
+    scene: DialogScene,
+    language: String,
+    cast: Vec<CharacterState>,
+    pending_label: Option<(String, usize, usize)>,
+    pending_background: Option<String>,
+    pending_transition: Option<DialogTransition>,
+    text_ids: HashMap<String, TextSlot>,
+    labels: HashMap<String, usize>,
+    references: Vec<LabelReference>,
+    translation_language: Option<String>,
+    line: usize,
+    /// column (นับตัวอักษร) ของต้นข้อความในบรรทัดปัจจุบัน
+    column: usize,
+    /// ข้อความของบรรทัดปัจจุบันหลังตัดย่อหน้า (offset ใน error นับเป็น byte ของข้อความนี้)
+    text: String,
+}
+
+/// แปลง `.hel` script เป็น `DialogScene` (ยังไม่ resolve label เป็น index)
+///
+/// `custom_actions` คือชื่อ action ที่ลงทะเบียนไว้ใน `ActionRegistry`
+pub fn parse_script(source: &str, custom_actions: &HashSet<String>) -> Result<DialogScene, ScriptError> {
+    let mut parser = ScriptParser {
+        custom_actions,
+        scene: DialogScene {
+            characters: Vec::new(),
+            entries: Vec::new(),
+            default_background: String::new(),
+        },
+        language: DEFAULT_LANGUAGE.to_string(),
+        cast: Vec::new(),
+        pending_label: None,
+        pending_background: None,
+        pending_transition: None,
+        text_ids: HashMap::new(),
+        labels: HashMap::new(),
+        references: Vec::new(),
+        translation_language: None,
+        line: 0,
+        column: 1,
+        text: String::new(),
+    };
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let text = raw_line.trim_start();
+        parser.line = index + 1;
+        parser.column = raw_line.chars().count() - text.chars().count() + 1;
+        parser.text = text.to_string();
+        parser.parse_line(text.trim_end())?;
+    }
+
+    if let Some((label, line, column)) = parser.pending_label {
+        return Err(ScriptError {
+            line,
+            column,
+            message: format!("label `{}` is not followed by a line", label),
+        });
+    }
+
+    for reference in &parser.references {
+        if !parser.labels.contains_key(&reference.label) {
+            return Err(ScriptError {
+                line: reference.line,
+                column: reference.column,
+                message: format!("unknown label `{}`", reference.label),
+            });
+        }
+    }
+
+    Ok(parser.scene)
+}
+
+impl ScriptParser<'_> {
+    /// สร้าง error ที่ตำแหน่ง `offset` (byte) นับจากต้นข้อความของบรรทัดปัจจุบัน
+    fn error(&self, offset: usize, message: impl Into<String>) -> ScriptError {
+        ScriptError {
+            line: self.line,
+            column: self.column_at(offset),
+            message: message.into(),
+        }
+    }
+
+    /// แปลง `offset` (byte) ในบรรทัดปัจจุบันเป็น column ที่นับตัวอักษร (ภาษาไทยหนึ่งตัวกินหลาย byte)
+    fn column_at(&self, offset: usize) -> usize {
+        self.column + self.text.get(..offset).map_or(offset, |prefix| prefix.chars().count())
+    }
+
+    fn parse_line(&mut self, text: &str) -> Result<(), ScriptError> {
+        if text.is_empty() || text.starts_with('#') {
+            return Ok(());
+        }
+
+        if let Some(language) = text.strip_prefix("===") {
+            let language = language.trim();
+            if language.is_empty() {
+                return Err(self.error(0, "translation block needs a language, e.g. `=== thai`"));
+            }
+            self.translation_language = Some(language.to_string());
+            return Ok(());
+        }
+
+        if let Some(language) = self.translation_language.clone() {
+            return self.parse_translation(&language, text);
+        }
+
+        if let Some(directive) = text.strip_prefix('@') {
+            return self.parse_directive(directive);
+        }
+
+        if let Some(choice) = text.strip_prefix('*') {
+            return self.parse_choice(choice);
+        }
+
+        if let Some(target) = text.strip_prefix("->") {
+            if self.scene.entries.is_empty() {
+                return Err(self.error(0, "`->` must follow a line"));
+            }
+            let target = self.parse_target(target, 2);
+            if let Some(entry) = self.scene.entries.last_mut() {
+                entry.auto_proceed = Some(target);
+            }
+            return Ok(());
+        }
+
+        self.parse_dialog_line(text)
+    }
+
+    fn parse_translation(&mut self, language: &str, text: &str) -> Result<(), ScriptError> {
+        let Some((id, translated)) = text.split_once(':') else {
+            return Err(self.error(0, "expected `id: translated text`"));
+        };
+
+        let target = match self.text_ids.get(id.trim()) {
+            Some(TextSlot::Entry(entry)) => &mut self.scene.entries[*entry].text,
+            Some(TextSlot::Choice(entry, choice)) => &mut self.scene.entries[*entry].choices[*choice].text,
+            None => return Err(self.error(0, format!("unknown text id `{}`", id.trim()))),
+        };
+        target.insert(language.to_string(), translated.trim().to_string());
+        Ok(())
+    }
+
+    fn parse_directive(&mut self, directive: &str) -> Result<(), ScriptError> {
+        let (name, args) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+        let args = args.trim();
+        let words: Vec<&str> = args.split_whitespace().collect();
+        // ตำแหน่งของ argument แรก (หลัง `@name `)
+        let args_offset = directive.len() - args.len() + 1;
+
+        match name {
+            "language" => {
+                let [language] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@language expects a language"));
+                };
+                self.language = language.to_string();
+            }
+            "character" => self.parse_character(args, args_offset)?,
+            "name" => {
+                let [character, language, display @ ..] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@name expects `<character> <language> <display name>`"));
+                };
+                let display = display.join(" ");
+                self.character_mut(character)
+                    .display_name
+                    .insert(language.to_string(), unquote(&display).to_string());
+            }
+            "default_bg" => self.scene.default_background = args.to_string(),
+            "bg" => self.pending_background = Some(args.to_string()),
+            "transition" => {
+                let [kind, rest @ ..] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@transition expects a type"));
+                };
+                let type_name: TransitionType = serde_json::from_value(serde_json::Value::String(kind.to_string()))
+                    .map_err(|_| self.error(args_offset, format!("unknown transition `{}`", kind)))?;
+                let duration = match rest.first() {
+                    Some(raw) => raw.parse::<f32>().map_err(|_| {
+                        self.error(args_offset + kind.len() + 1, format!("invalid duration `{}`", raw))
+                    })?,
+                    None => DEFAULT_TRANSITION_DURATION,
+                };
+                self.pending_transition = Some(DialogTransition { type_name, duration });
+            }
+            "show" => {
+                let [character, rest @ ..] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@show expects `<character> [position] [expression]`"));
+                };
+                self.character_mut(character);
+
+                let state = CharacterState {
+                    name: character.to_string(),
+                    position: rest.first().copied().unwrap_or("center").to_string(),
+                    expression: rest.get(1).copied().unwrap_or("default").to_string(),
+                    highlight: rest.get(2) == Some(&"highlight"),
+                };
+                match self.cast.iter_mut().find(|c| c.name == *character) {
+                    Some(existing) => *existing = state,
+                    None => self.cast.push(state),
+                }
+            }
+            "hide" => {
+                let [character] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@hide expects a character"));
+                };
+                self.cast.retain(|c| c.name != *character);
+            }
+            "label" => {
+                let [label] = words.as_slice() else {
+                    return Err(self.error(args_offset, "@label expects a name"));
+                };
+                if let Some((previous, _, _)) = &self.pending_label {
+                    return Err(self.error(0, format!("label `{}` is not followed by a line", previous)));
+                }
+                if self.labels.contains_key(*label) {
+                    return Err(self.error(args_offset, format!("label `{}` is already used", label)));
+                }
+                self.pending_label = Some((label.to_string(), self.line, self.column_at(args_offset)));
+            }
+            "jump" => {
+                if self.scene.entries.is_empty() {
+                    return Err(self.error(0, "@jump must follow a line"));
+                }
+                let (target, condition) = match args.split_once(" if ") {
+                    Some((target, condition)) => {
+                        let condition_offset = args_offset + target.len() + 4;
+                        let condition = condition.trim();
+                        let condition = Condition::parse(condition).map_err(|e| {
+                            self.error(condition_offset + e.position, format!("invalid condition: {}", e.message))
+                        })?;
+                        (target, Some(condition))
+                    }
+                    None => (args, None),
+                };
+                let target = self.parse_target(target, args_offset);
+                if let Some(entry) = self.scene.entries.last_mut() {
+                    entry.actions.push(DialogAction::Jump { target, condition });
+                }
+            }
+            _ => {
+                let args = split_args(args)
+                    .map_err(|offset| self.error(args_offset + offset, "unterminated quoted argument"))?;
+                let action = DialogAction::from_args(name, &args).map_err(|e| self.error(args_offset, e))?;
+                if matches!(&action, DialogAction::Custom { name, .. } if !self.custom_actions.contains(name)) {
+                    return Err(self.error(1, format!("unknown action `{}`", name)));
+                }
+                let Some(entry) = self.scene.entries.last_mut() else {
+                    return Err(self.error(0, format!("@{} must follow a line", name)));
+                };
+                entry.actions.push(action);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_character(&mut self, args: &str, args_offset: usize) -> Result<(), ScriptError> {
+        let (name, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        if name.is_empty() {
+            return Err(self.error(args_offset, "@character expects a name"));
+        }
+
+        let mut rest = rest.trim_start();
+        let mut display_name = None;
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let Some(end) = quoted.find('"') else {
+                return Err(self.error(args_offset + args.len() - rest.len(), "unterminated display name"));
+            };
+            display_name = Some(quoted[..end].to_string());
+            rest = &quoted[end + 1..];
+        }
+
+        let mut sprite = None;
+        for option in rest.split_whitespace() {
+            match option.split_once('=') {
+                Some(("sprite", path)) => sprite = Some(path.to_string()),
+                _ => {
+                    let offset = args_offset + args.find(option).unwrap_or(0);
+                    return Err(self.error(offset, format!("unknown character option `{}`", option)));
+                }
+            }
+        }
+
+        let language = self.language.clone();
+        let character = self.character_mut(name);
+        if let Some(display_name) = display_name {
+            character.display_name.insert(language, display_name);
+        }
+        if let Some(sprite) = sprite {
+            character.sprite = sprite;
+        }
+        Ok(())
+    }
+
+    fn parse_choice(&mut self, choice: &str) -> Result<(), ScriptError> {
+        let Some(entry_index) = self.scene.entries.len().checked_sub(1) else {
+            return Err(self.error(0, "a choice must follow a line"));
+        };
+
+        let trimmed = choice.trim();
+        let (id, body) = split_text_id(trimmed);
+        let body_offset = 1 + (choice.len() - choice.trim_start().len()) + (trimmed.len() - body.len());
+
+        let Some((text, target)) = body.rsplit_once("->") else {
+            return Err(self.error(body_offset, "choice needs a target, e.g. `* text -> label`"));
+        };
+        let target_offset = body_offset + text.len() + 2;
+
+        let (target, conditions) = match target.split_once(" if ") {
+            Some((target, condition)) => {
+                let condition_offset = target_offset + target.len() + 4;
+                let condition = condition.trim();
+                let condition = Condition::parse(condition).map_err(|e| {
+                    self.error(condition_offset + e.position, format!("invalid condition: {}", e.message))
+                })?;
+                (target, vec![condition])
+            }
+            None => (target, Vec::new()),
+        };
+
+        let choice = DialogChoice {
+            text: HashMap::from([(self.language.clone(), text.trim().to_string())]),
+            target: self.parse_target(target, target_offset),
+            conditions,
+            show_locked: false,
+        };
+
+        let entry = &mut self.scene.entries[entry_index];
+        entry.choices.push(choice);
+        let slot = TextSlot::Choice(entry_index, entry.choices.len() - 1);
+        if let Some(id) = id {
+            self.register_text_id(id, slot)?;
+        }
+        Ok(())
+    }
+
+    fn parse_dialog_line(&mut self, text: &str) -> Result<(), ScriptError> {
+        let (id, body) = split_text_id(text);
+        let (character, line_text) = match body.split_once(':') {
+            Some((speaker, rest)) if is_identifier(speaker) => (speaker.to_string(), rest.trim()),
+            _ => (NARRATOR.to_string(), body),
+        };
+        self.character_mut(&character);
+
+        let label = self.pending_label.take().map(|(label, _, _)| label);
+        if let Some(label) = &label {
+            self.labels.insert(label.clone(), self.scene.entries.len());
+        }
+
+        self.scene.entries.push(DialogEntry {
+            label,
+            character,
+            text: HashMap::from([(self.language.clone(), line_text.to_string())]),
+            actions: Vec::new(),
+            choices: Vec::new(),
+            auto_proceed: None,
+            character_states: self.cast.clone(),
+            background: self.pending_background.take(),
+            transition: self.pending_transition.take(),
+        });
+
+        if let Some(id) = id {
+            self.register_text_id(id, TextSlot::Entry(self.scene.entries.len() - 1))?;
+        }
+        Ok(())
+    }
+
+    /// `label`, `scene#label` หรือ index ตรงๆ; label ภายใน scene จะถูกตรวจหลังอ่านจบไฟล์
+    fn parse_target(&mut self, target: &str, offset: usize) -> JumpTarget {
+        let offset = offset + target.len() - target.trim_start().len();
+        let target = target.trim();
+        let target = match target.parse::<usize>() {
+            Ok(stage) => JumpTarget::Stage(stage),
+            Err(_) => JumpTarget::parse(target),
+        };
+
+        if let JumpTarget::Label(label) = &target {
+            self.references.push(LabelReference {
+                label: label.clone(),
+                line: self.line,
+                column: self.column_at(offset),
+            });
+        }
+        target
+    }
+
+    fn register_text_id(&mut self, id: &str, slot: TextSlot) -> Result<(), ScriptError> {
+        if self.text_ids.contains_key(id) {
+            return Err(self.error(1, format!("text id `{}` is used more than once", id)));
+        }
+        self.text_ids.insert(id.to_string(), slot);
+        Ok(())
+    }
+
+    fn character_mut(&mut self, name: &str) -> &mut DialogCharacter {
+        let index = match self.scene.characters.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.scene.characters.push(DialogCharacter {
+                    name: name.to_string(),
+                    display_name: HashMap::new(),
+                    sprite: String::new(),
+                    positions: HashMap::new(),
+                });
+                self.scene.characters.len() - 1
+            }
+        };
+        &mut self.scene.characters[index]
+    }
+}
+
+/// `[id] rest` -> (Some(id), rest) (`[b]` / `[i]` เป็น markup ไม่ใช่ id)
+fn split_text_id(text: &str) -> (Option<&str>, &str) {
+    text.strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .filter(|(id, _)| is_identifier(id) && !matches!(*id, "b" | "i"))
+        .map(|(id, body)| (Some(id), body.trim_start()))
+        .unwrap_or((None, text))
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// แยก argument ด้วยช่องว่าง ยกเว้นในเครื่องหมายคำพูด (`name="Miki san"` เป็น argument เดียว)
+/// argument ที่ครอบด้วย `"` ทั้งตัวถูกถอด `"` ออก ถ้า `"` ไม่ปิดคืน offset (byte) ของ `"` นั้น
+pub(crate) fn split_args(args: &str) -> Result<Vec<String>, usize> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote_start = None;
+    for (offset, c) in args.char_indices() {
+        if c == '"' {
+            quote_start = if quote_start.is_some() { None } else { Some(offset) };
+        } else if c.is_whitespace() && quote_start.is_none() {
+            if !current.is_empty() {
+                result.push(unquote(&current).to_string());
+                current.clear();
+            }
+            continue;
+        }
+        current.push(c);
+    }
+    if let Some(offset) = quote_start {
+        return Err(offset);
+    }
+    if !current.is_empty() {
+        result.push(unquote(&current).to_string());
+    }
+    Ok(result)
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::story::variables::StoryValue;
+
+    const SCENE: &str = r#"@language english
+@character miki "Miki" sprite=characters/miki.png
+@name miki thai มิกิ
+
+@label lunch
+[ask] miki: Where would you like to go first?
+* [opt_food] The cafeteria -> cafeteria
+* The library -> library if affection.miki >= 3
+@set_var nickname="Miki san"
+
+@label cafeteria
+The cafeteria is crowded.
+
+@label library
+It is quiet here.
+
+=== thai
+ask: อยากไปที่ไหนก่อนดี?
+opt_food: โรงอาหาร
+"#;
+
+    fn parse(source: &str) -> Result<DialogScene, ScriptError> {
+        parse_script(source, &HashSet::new())
+    }
+
+    fn error_at(source: &str) -> (usize, usize) {
+        let error = parse(source).unwrap_err();
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn parses_lines_choices_and_translations() {
+        let scene = parse(SCENE).unwrap();
+        assert_eq!(scene.entries.len(), 3);
+
+        let ask = &scene.entries[0];
+        assert_eq!(ask.label.as_deref(), Some("lunch"));
+        assert_eq!(ask.character, "miki");
+        assert_eq!(ask.text["english"], "Where would you like to go first?");
+        assert_eq!(ask.text["thai"], "อยากไปที่ไหนก่อนดี?");
+        assert_eq!(ask.choices.len(), 2);
+        assert_eq!(ask.choices[0].text["thai"], "โรงอาหาร");
+        assert_eq!(ask.choices[1].conditions, vec![Condition::parse("affection.miki >= 3").unwrap()]);
+        assert_eq!(
+            ask.actions,
+            vec![DialogAction::SetVar { name: "nickname".to_string(), value: StoryValue::Str("Miki san".to_string()) }]
+        );
+
+        assert_eq!(scene.entries[1].character, NARRATOR);
+        let miki = scene.characters.iter().find(|c| c.name == "miki").unwrap();
+        assert_eq!(miki.display_name["thai"], "มิกิ");
+        assert_eq!(miki.sprite, "characters/miki.png");
+    }
+
+    #[test]
+    fn error_columns_count_characters() {
+        // `ภาพ=x` เริ่มที่ตัวอักษรที่ 26 แม้จะอยู่ที่ byte ที่ 40 กว่า
+        assert_eq!(error_at("@character ผู้เล่า \"ก ข\" ภาพ=x\n"), (1, 26));
+        assert_eq!(error_at("สวัสดี\n@transition fade_in เร็ว\n"), (2, 21));
+        assert_eq!(error_at("สวัสดี\n  * ภาษาไทย ไม่มีลูกศร\n"), (2, 5));
+    }
+
+    #[test]
+    fn action_errors_point_at_the_action() {
+        assert_eq!(error_at("Hi\n@set_var name=\"Miki\n"), (2, 15));
+        assert_eq!(error_at("Hi\n@shake 10 -1\n"), (2, 8));
+        assert_eq!(error_at("Hi\n@bogus 1\n"), (2, 2));
+        assert_eq!(error_at("Hi\n* Go -> there if a &&\n"), (2, 22));
+    }
+}
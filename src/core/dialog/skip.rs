@@ -0,0 +1,79 @@
+//! โหมดข้ามข้อความ: กด `Skip` ค้าง (Ctrl) หรือกด `SkipToggle` (S) เปิด/ปิด หยุดเมื่อถึงตัวเลือก
+//!
+//! ถ้าไม่ได้เปิด `SettingsResource.skip_unread` จะข้ามเฉพาะบรรทัดที่อยู่ใน `ReadLog` และ
+//! โหมด `SkipToggle` จะปิดเองเมื่อเจอบรรทัดที่ยังไม่เคยอ่าน
+
+use bevy::prelude::*;
+use crate::core::dialog::action::ActionWait;
+use crate::core::dialog::choice::ChoiceState;
+use crate::core::input::action::{ActionState, InputAction};
+use crate::core::resources::{DialogManager, SettingsResource, VNState};
+use crate::core::save::read_log::ReadLog;
+
+/// เวลาขั้นต่ำระหว่างบรรทัดที่ข้าม
+const SKIP_INTERVAL: f32 = 0.05;
+
+#[derive(Resource, Default)]
+pub struct SkipState {
+    /// เปิดค้างด้วย `SkipToggle`
+    pub toggled: bool,
+    /// กด `Skip` ค้างอยู่
+    pub held: bool,
+    /// ข้ามบรรทัดปัจจุบันใน frame นี้ (ตั้งโดย `update_skip` ใช้ใน `handle_text_interaction`)
+    pub advance: bool,
+    elapsed: f32,
+}
+
+impl SkipState {
+    pub fn is_active(&self) -> bool {
+        self.toggled || self.held
+    }
+
+    pub fn stop(&mut self) {
+        self.toggled = false;
+        self.advance = false;
+    }
+}
+
+pub fn handle_skip_input(actions: Res<ActionState>, mut skip: ResMut<SkipState>) {
+    skip.held = actions.pressed(InputAction::Skip);
+    if actions.just_pressed(InputAction::SkipToggle) {
+        skip.toggled = !skip.toggled;
+    }
+}
+
+/// ตัดสินว่า frame นี้ข้ามบรรทัดได้หรือไม่ (ต้องรันก่อน `handle_text_interaction`)
+pub fn update_skip(
+    time: Res<Time>,
+    state: Res<VNState>,
+    settings: Res<SettingsResource>,
+    read_log: Res<ReadLog>,
+    choice_state: Res<ChoiceState>,
+    dialog_manager: Res<DialogManager>,
+    mut action_wait: ResMut<ActionWait>,
+    mut skip: ResMut<SkipState>,
+) {
+    skip.advance = false;
+    if !skip.is_active() {
+        skip.elapsed = 0.0;
+        return;
+    }
+    if choice_state.active {
+        skip.stop();
+        return;
+    }
+    if state.should_reset_dialog() || dialog_manager.is_processing() {
+        return;
+    }
+    if !settings.skip_unread && !read_log.is_read(&state.current_scene, state.stage) {
+        skip.stop();
+        return;
+    }
+
+    action_wait.finish();
+    skip.elapsed += time.delta_seconds();
+    if skip.elapsed >= SKIP_INTERVAL {
+        skip.elapsed = 0.0;
+        skip.advance = true;
+    }
+}
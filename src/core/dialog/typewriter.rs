@@ -1,20 +1,313 @@
 use bevy::prelude::*;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::core::language::types::LanguageCode;
+use crate::core::text::components::TextStylePreset;
+use crate::core::text::linebreak::{insert_thai_breaks, ZWSP};
+use crate::core::text::markup::{parse_markup, MarkupSpan};
+use crate::core::text::styles::TextStyleResource;
+
+/// เศษเวลาที่ถือว่า `{w=...}` หมดแล้ว (กัน f32 ปัดเศษจนรอเกินไปหนึ่ง frame)
+const DELAY_EPSILON: f32 = 0.001;
+/// ค่าสูงสุดของ `{w=...}` / `{speed=...}` (วินาที) ค่าที่เกินหรือไม่ใช่ตัวเลขจริงถือว่าไม่ใช่ tag
+const MAX_TAG_SECONDS: f32 = 3600.0;
+
+/// หยุดหลังเครื่องหมายวรรคตอน (เท่าของเวลาต่อตัวอักษร): `、` สั้น, `。` ยาวกว่า
+fn punctuation_pause(grapheme: &str) -> f32 {
+    match grapheme {
+        "、" | "，" => 3.0,
+        "。" | "！" | "？" | "…" => 6.0,
+        _ => 0.0,
+    }
+}
+
+/// tag ควบคุมการพิมพ์ที่ฝังในข้อความ
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypewriterTag {
+    /// `{w=0.5}` หยุดชั่วครู่ (วินาที)
+    Wait(f32),
+    /// `{speed=0.02}` เปลี่ยนเวลาต่อตัวอักษร (`0` = แสดงทันที)
+    Speed(f32),
+    /// `{/speed}` กลับไปใช้ความเร็วปกติ
+    DefaultSpeed,
+    /// `{p}` รอผู้เล่นคลิกก่อนพิมพ์ต่อ
+    ClickWait,
+    /// `{nw}` ไปบรรทัดถัดไปเองเมื่อพิมพ์จบ
+    NoWait,
+}
+
+impl TypewriterTag {
+    fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "p" => Some(TypewriterTag::ClickWait),
+            "nw" => Some(TypewriterTag::NoWait),
+            "/speed" => Some(TypewriterTag::DefaultSpeed),
+            _ => {
+                let (name, value) = tag.split_once('=')?;
+                let value: f32 = value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|value: &f32| value.is_finite() && (0.0..=MAX_TAG_SECONDS).contains(value))?;
+                match name.trim() {
+                    "w" => Some(TypewriterTag::Wait(value)),
+                    "speed" => Some(TypewriterTag::Speed(value)),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct TypewriterText {
+    /// ข้อความที่ตัด markup และ tag ออกแล้ว
     pub full_text: String,
     pub current_text: String,
     pub timer: Timer,
-    pub char_index: usize,
+    /// ตำแหน่ง byte ใน `full_text` ที่แสดงถึงแล้ว (อยู่ที่ขอบ grapheme เสมอ)
+    pub cursor: usize,
+    /// ช่วงข้อความตาม markup แต่ละช่วงคือ `TextSection` หนึ่งอัน
+    pub spans: Vec<MarkupSpan>,
+    /// tag พร้อมตำแหน่ง byte ใน `full_text` (เรียงตามตำแหน่ง)
+    pub tags: Vec<(usize, TypewriterTag)>,
+    next_tag: usize,
+    base_speed: f32,
+    /// เวลาที่เหลือของ `{w=...}`
+    pub delay: f32,
+    /// หยุดอยู่ที่ `{p}`
+    pub waiting_for_click: bool,
+    /// ตำแหน่ง byte ที่พิมพ์ต่อจาก `{p}` ครั้งล่าสุด (ข้อความก่อนหน้านี้ผู้เล่นอ่านไปแล้ว)
+    read_from: usize,
+    /// ผ่าน `{nw}` แล้ว
+    pub no_wait: bool,
 }
 
 impl TypewriterText {
     pub fn new(text: &str, speed: f32) -> Self {
+        let (spans, tags) = extract_tags(parse_markup(&insert_thai_breaks(text)));
         TypewriterText {
-            full_text: text.to_string(),
+            full_text: spans.iter().map(|span| span.text.as_str()).collect(),
             current_text: String::new(),
             timer: Timer::from_seconds(speed, TimerMode::Repeating),
-            char_index: 0,
+            cursor: 0,
+            spans,
+            tags,
+            next_tag: 0,
+            base_speed: speed,
+            delay: 0.0,
+            waiting_for_click: false,
+            read_from: 0,
+            no_wait: false,
+        }
+    }
+
+    /// พิมพ์ครบทุกตัวอักษรและผ่านทุก tag แล้ว
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.full_text.len()
+            && self.next_tag >= self.tags.len()
+            && !self.waiting_for_click
+            && self.delay <= 0.0
+    }
+
+    /// จำนวน grapheme ที่แสดงแล้วตั้งแต่ `{p}` ล่าสุด (ไม่นับช่องว่างและจุดตัดคำ)
+    pub fn unread_grapheme_count(&self) -> usize {
+        self.full_text[self.read_from..self.cursor]
+            .graphemes(true)
+            .filter(|grapheme| !grapheme.chars().all(|c| c.is_whitespace() || c == ZWSP))
+            .count()
+    }
+
+    /// เดินเวลา คืน `true` ถ้ามีตัวอักษรแสดงเพิ่ม
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        let mut revealed = false;
+        let mut budget = None;
+        loop {
+            self.apply_tags(false);
+            if self.waiting_for_click {
+                break;
+            }
+            // นับ `{w=...}` ก่อนเช็คจบข้อความ `{w}` ท้ายบรรทัดจึงหมดเวลาได้
+            if self.delay > 0.0 {
+                self.delay -= delta.as_secs_f32();
+                if self.delay > DELAY_EPSILON {
+                    break;
+                }
+                self.delay = 0.0;
+            }
+            if self.cursor >= self.full_text.len() {
+                break;
+            }
+
+            // `{speed=0}` แสดงทันทีจนกว่าจะเจอ `{/speed}`
+            if !self.timer.duration().is_zero() {
+                let budget = budget.get_or_insert_with(|| {
+                    self.timer.tick(delta);
+                    self.timer.times_finished_this_tick()
+                });
+                if *budget == 0 {
+                    break;
+                }
+                *budget -= 1;
+            }
+            let pause = punctuation_pause(self.reveal_next());
+            revealed = true;
+            if pause > 0.0 && self.cursor < self.full_text.len() {
+                self.delay = pause * self.timer.duration().as_secs_f32();
+                break;
+            }
+        }
+        revealed
+    }
+
+    /// ผู้เล่นคลิกระหว่างพิมพ์: ไปต่อจาก `{p}` หรือข้ามไปจุดรอถัดไป (หรือจบบรรทัด)
+    pub fn skip_to_wait(&mut self) {
+        if self.waiting_for_click {
+            self.resume();
+            return;
+        }
+        loop {
+            self.apply_tags(true);
+            if self.waiting_for_click || self.cursor >= self.full_text.len() {
+                break;
+            }
+            self.reveal_next();
+        }
+        self.delay = 0.0;
+    }
+
+    /// พิมพ์ต่อจาก `{p}`
+    pub fn resume(&mut self) {
+        if self.waiting_for_click {
+            self.waiting_for_click = false;
+            self.read_from = self.cursor;
         }
     }
-}
\ No newline at end of file
+
+    /// ข้ามไปแสดงข้อความทั้งหมดทันที (ไม่หยุดที่ `{p}`)
+    pub fn finish(&mut self) {
+        while self.next_tag < self.tags.len() {
+            self.apply_tag(self.next_tag, true);
+            self.next_tag += 1;
+        }
+        self.waiting_for_click = false;
+        self.current_text = self.full_text.clone();
+        self.cursor = self.full_text.len();
+    }
+
+    /// `TextSection` ของแต่ละ span เป็นคู่ (ส่วนที่แสดงแล้ว, ส่วนที่ยังซ่อนแบบโปร่งใส) ใช้คู่กับ `reveal`
+    ///
+    /// ข้อความทั้งบรรทัดถูกจัด layout ตั้งแต่แรก คำที่ยังพิมพ์ไม่ครบจึงไม่กระโดดขึ้นบรรทัดใหม่ระหว่างพิมพ์
+    pub fn sections(&self, preset: &TextStylePreset, text_styles: &TextStyleResource, language: &LanguageCode) -> Vec<TextSection> {
+        if self.spans.is_empty() {
+            return vec![TextSection::new("", preset.to_style(text_styles, language))];
+        }
+        self.spans
+            .iter()
+            .flat_map(|span| {
+                let style = span.style(preset, text_styles, language);
+                let mut hidden = style.clone();
+                hidden.color = hidden.color.with_alpha(0.0);
+                [TextSection::new("", style), TextSection::new("", hidden)]
+            })
+            .collect()
+    }
+
+    /// แสดงข้อความถึง `cursor` กระจายไปตาม section ของแต่ละ span ที่เหลือใส่ใน section โปร่งใส
+    pub fn reveal(&self, text: &mut Text) {
+        let mut remaining = self.cursor;
+        for (index, pair) in text.sections.chunks_mut(2).enumerate() {
+            let span = self.spans.get(index).map(|span| span.text.as_str()).unwrap_or_default();
+            let end = remaining.min(span.len());
+            remaining -= end;
+            pair[0].value = span[..end].to_string();
+            if let Some(hidden) = pair.get_mut(1) {
+                hidden.value = span[end..].to_string();
+            }
+        }
+    }
+
+    /// แสดง grapheme ถัดไป (สระและวรรณยุกต์ไทยมาพร้อมพยัญชนะ) คืน grapheme นั้น
+    fn reveal_next(&mut self) -> &str {
+        let start = self.cursor;
+        let Some(grapheme) = self.full_text[start..].graphemes(true).next() else {
+            return "";
+        };
+        self.cursor += grapheme.len();
+        self.current_text.push_str(grapheme);
+        let end = self.cursor;
+        // จุดตัดบรรทัดไม่มีความกว้าง ไม่ต้องเสียจังหวะพิมพ์
+        while self.full_text[self.cursor..].starts_with(ZWSP) {
+            self.cursor += ZWSP.len_utf8();
+            self.current_text.push(ZWSP);
+        }
+        &self.full_text[start..end]
+    }
+
+    /// ใช้ tag ทุกตัวที่อยู่ถึงตำแหน่งปัจจุบัน หยุดที่ `{p}`
+    fn apply_tags(&mut self, skipping: bool) {
+        while let Some((position, tag)) = self.tags.get(self.next_tag) {
+            if *position > self.cursor {
+                break;
+            }
+            let click_wait = *tag == TypewriterTag::ClickWait;
+            self.apply_tag(self.next_tag, skipping);
+            self.next_tag += 1;
+            if click_wait {
+                self.waiting_for_click = true;
+                break;
+            }
+        }
+    }
+
+    fn apply_tag(&mut self, index: usize, skipping: bool) {
+        match self.tags[index].1 {
+            TypewriterTag::Wait(seconds) if !skipping => self.delay += seconds,
+            TypewriterTag::Wait(_) | TypewriterTag::ClickWait => {}
+            TypewriterTag::Speed(seconds) => self.timer.set_duration(Duration::from_secs_f32(seconds)),
+            TypewriterTag::DefaultSpeed => self.timer.set_duration(Duration::from_secs_f32(self.base_speed)),
+            TypewriterTag::NoWait => self.no_wait = true,
+        }
+    }
+}
+
+/// ข้อความที่ผู้เล่นเห็น (ตัดทั้ง markup และ tag ของ typewriter)
+pub fn plain_text(text: &str) -> String {
+    extract_tags(parse_markup(text)).0.into_iter().map(|span| span.text).collect()
+}
+
+/// ตัด `{w=...}`, `{speed=...}`, `{/speed}`, `{p}`, `{nw}` ออกจากข้อความ พร้อมจำตำแหน่ง byte
+fn extract_tags(spans: Vec<MarkupSpan>) -> (Vec<MarkupSpan>, Vec<(usize, TypewriterTag)>) {
+    let mut tags = Vec::new();
+    let mut position = 0;
+    let spans = spans
+        .into_iter()
+        .map(|mut span| {
+            let mut text = String::with_capacity(span.text.len());
+            let mut rest = span.text.as_str();
+            while let Some(start) = rest.find('{') {
+                let tail = &rest[start..];
+                let Some((end, tag)) = tail
+                    .find('}')
+                    .and_then(|end| TypewriterTag::parse(&tail[1..end]).map(|tag| (end, tag)))
+                else {
+                    text.push_str(&rest[..=start]);
+                    position += start + 1;
+                    rest = &rest[start + 1..];
+                    continue;
+                };
+
+                text.push_str(&rest[..start]);
+                position += start;
+                tags.push((position, tag));
+                rest = &tail[end + 1..];
+            }
+            position += rest.len();
+            text.push_str(rest);
+            span.text = text;
+            span
+        })
+        .filter(|span| !span.text.is_empty())
+        .collect();
+    (spans, tags)
+}
@@ -0,0 +1,961 @@
+//! Importer สำหรับ Yarn Spinner (`.yarn`)
+//!
+//! ```text
+//! title: Start
+//! ---
+//! <<declare $affection = 0>>
+//! <<character Miki "Miki" sprite=characters/miki.png>>
+//! <<bg backgrounds/school_rooftop.png>>
+//! <<show Miki center happy>>
+//! Miki: You came! #line:miki_01
+//! -> Of course.
+//!     <<set $affection += 1>>
+//! -> I was just passing by. <<if $affection < 1>>
+//!     Miki: Oh...
+//! <<if $affection >= 1>>
+//!     <<play_bgm romance.ogg>>
+//! <<endif>>
+//! <<jump Ending>>
+//! ===
+//! ```
+//!
+//! - node กลายเป็นกลุ่ม entry ที่มี label เป็น `title` (node `Start` ถูกวางไว้ต้น scene)
+//! - `->` ตัวเลือก (เนื้อหาย่อหน้าใต้ตัวเลือก), `<<if>>` / `<<elseif>>` / `<<else>>` กลายเป็น `jump` action
+//! - `<<set>>` / `<<declare>>` รองรับค่าคงที่, `$x + n`, `$x - n`, `+=` และ `-=`
+//! - `<<bg>>`, `<<show>>`, `<<hide>>`, `<<transition>>`, `<<character>>` ใช้รูปแบบเดียวกับ `.hel`
+//! - `<<command args>>` อื่นๆ เป็น action (`<<wait 2>>`, `<<play_bgm school.ogg>>`, custom action)
+//! - `#tag` ท้ายบรรทัดและ `// comment` ถูกข้าม
+//!
+//! - `{$x}` ในข้อความและตัวเลือกแสดงค่าตัวแปร (แปลงเป็น `{var:x}`)
+//!
+//! ยังไม่รองรับ function, `<<once>>`, `=>` line group และ option แบบ `[[...]]` ของ Yarn 1
+
+use std::collections::{HashMap, HashSet};
+use crate::core::dialog::action::DialogAction;
+use crate::core::dialog::script::{split_args, ScriptError};
+use crate::core::story::condition::Condition;
+use crate::core::story::variables::StoryValue;
+use crate::types::{
+    CharacterState, DialogCharacter, DialogChoice, DialogEntry, DialogScene, DialogTransition,
+    JumpTarget, TransitionType,
+};
+
+const DEFAULT_LANGUAGE: &str = "english";
+const NARRATOR: &str = "narrator";
+const START_NODE: &str = "Start";
+const DEFAULT_TRANSITION_DURATION: f32 = 0.5;
+
+/// บรรทัดใน body ของ node หลังตัด comment และ tag แล้ว
+struct Line<'s> {
+    number: usize,
+    /// ความลึกของย่อหน้า (tab นับเป็น 4)
+    indent: usize,
+    column: usize,
+    text: &'s str,
+}
+
+struct Node<'s> {
+    title: String,
+    line: usize,
+    body: Vec<Line<'s>>,
+}
+
+enum Statement {
+    Line { speaker: Option<String>, text: String },
+    Action(DialogAction),
+    Background(String),
+    Transition(DialogTransition),
+    Show(CharacterState),
+    Hide(String),
+    Character { name: String, display_name: Option<String>, sprite: Option<String> },
+    Jump(String),
+    Stop,
+    /// branch ที่ไม่มีเงื่อนไขคือ `<<else>>`
+    If(Vec<(Option<Condition>, Vec<Statement>)>),
+    Options(Vec<YarnOption>),
+}
+
+struct YarnOption {
+    text: String,
+    condition: Option<Condition>,
+    body: Vec<Statement>,
+}
+
+/// node ที่ถูกอ้างถึงด้วย `<<jump>>` เก็บตำแหน่งไว้รายงาน error
+struct NodeReference {
+    title: String,
+    line: usize,
+    column: usize,
+}
+
+struct Parser<'a, 's> {
+    custom_actions: &'a HashSet<String>,
+    lines: &'a [Line<'s>],
+    index: usize,
+    declarations: Vec<DialogAction>,
+    references: Vec<NodeReference>,
+}
+
+/// ตำแหน่งใน entry ที่ต้องเติม stage หลัง compile ครบทุก node
+enum Fixup {
+    AutoProceed(usize),
+    Choice(usize, usize),
+    Jump(usize, usize),
+}
+
+struct Compiler {
+    scene: DialogScene,
+    cast: Vec<CharacterState>,
+    pending_label: Option<String>,
+    pending_background: Option<String>,
+    pending_transition: Option<DialogTransition>,
+    pending_actions: Vec<DialogAction>,
+    /// position ที่ยังรอ entry ถัดไป
+    unbound: Vec<usize>,
+    positions: Vec<Option<usize>>,
+    fixups: Vec<(Fixup, usize)>,
+    node_start: usize,
+    terminated: bool,
+}
+
+/// แปลง `.yarn` เป็น `DialogScene` (ยังไม่ resolve label ของ node เป็น index)
+///
+/// `custom_actions` คือชื่อ action ที่ลงทะเบียนไว้ใน `ActionRegistry`
+pub fn import_yarn(source: &str, custom_actions: &HashSet<String>) -> Result<DialogScene, ScriptError> {
+    let mut nodes = split_nodes(source)?;
+    // Yarn เริ่มเล่นที่ node `Start` เสมอ
+    if let Some(start) = nodes.iter().position(|node| node.title == START_NODE) {
+        let start = nodes.remove(start);
+        nodes.insert(0, start);
+    }
+
+    let mut titles = HashSet::new();
+    for node in &nodes {
+        if !titles.insert(node.title.as_str()) {
+            return Err(ScriptError {
+                line: node.line,
+                column: 1,
+                message: format!("node `{}` is already defined", node.title),
+            });
+        }
+    }
+
+    let mut declarations = Vec::new();
+    let mut references = Vec::new();
+    let mut bodies = Vec::new();
+    for node in &nodes {
+        let mut parser = Parser {
+            custom_actions,
+            lines: &node.body,
+            index: 0,
+            declarations: Vec::new(),
+            references: Vec::new(),
+        };
+        let body = parser.block(None)?;
+        if let Some(line) = parser.lines.get(parser.index) {
+            return Err(parser.error(line, 0, format!("unexpected `{}`", line.text)));
+        }
+        declarations.append(&mut parser.declarations);
+        references.append(&mut parser.references);
+        bodies.push((node.title.clone(), body));
+    }
+
+    for reference in &references {
+        if !titles.contains(reference.title.as_str()) {
+            return Err(ScriptError {
+                line: reference.line,
+                column: reference.column,
+                message: format!("unknown node `{}`", reference.title),
+            });
+        }
+    }
+
+    let mut compiler = Compiler {
+        scene: DialogScene {
+            characters: Vec::new(),
+            entries: Vec::new(),
+            default_background: String::new(),
+        },
+        cast: Vec::new(),
+        pending_label: None,
+        pending_background: None,
+        pending_transition: None,
+        pending_actions: Vec::new(),
+        unbound: Vec::new(),
+        positions: Vec::new(),
+        fixups: Vec::new(),
+        node_start: 0,
+        terminated: false,
+    };
+    compiler.character(NARRATOR);
+
+    // ค่าเริ่มต้นของ `<<declare>>` ถูกตั้งทุกครั้งที่เริ่ม scene จาก entry แรก
+    if !declarations.is_empty() {
+        compiler.emit(control_entry(declarations));
+    }
+    for (title, body) in bodies {
+        compiler.compile_node(title, &body);
+    }
+    compiler.apply_fixups();
+
+    Ok(compiler.scene)
+}
+
+/// แยกไฟล์เป็น node ตาม header (`title: ...`), `---` และ `===`
+fn split_nodes(source: &str) -> Result<Vec<Node<'_>>, ScriptError> {
+    let mut nodes = Vec::new();
+    let mut title: Option<(String, usize)> = None;
+    let mut body: Option<Vec<Line>> = None;
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let number = index + 1;
+        let trimmed = raw_line.trim();
+
+        let Some(lines) = body.as_mut() else {
+            if trimmed == "---" {
+                if title.is_none() {
+                    return Err(ScriptError {
+                        line: number,
+                        column: 1,
+                        message: "node is missing a `title:` header".to_string(),
+                    });
+                }
+                body = Some(Vec::new());
+            } else if let Some(value) = trimmed.strip_prefix("title:") {
+                title = Some((value.trim().to_string(), number));
+            }
+            continue;
+        };
+
+        if trimmed == "===" {
+            let (title, line) = title.take().unwrap_or_default();
+            nodes.push(Node {
+                title,
+                line,
+                body: body.take().unwrap_or_default(),
+            });
+            continue;
+        }
+
+        let text = strip_comment(raw_line.trim_start()).trim_end();
+        if !text.is_empty() {
+            lines.push(Line {
+                number,
+                indent: indent_width(raw_line),
+                column: raw_line.chars().count() - raw_line.trim_start().chars().count() + 1,
+                text,
+            });
+        }
+    }
+
+    if let Some((title, line)) = title {
+        return Err(ScriptError {
+            line,
+            column: 1,
+            message: format!("node `{}` is missing its closing `===`", title),
+        });
+    }
+    Ok(nodes)
+}
+
+/// แปลง `offset` (byte) ใน `line.text` เป็น column ที่นับตัวอักษร
+fn column_at(line: &Line, offset: usize) -> usize {
+    line.column + line.text.get(..offset).map_or(offset, |prefix| prefix.chars().count())
+}
+
+impl<'s> Parser<'_, 's> {
+    fn error(&self, line: &Line, offset: usize, message: impl Into<String>) -> ScriptError {
+        ScriptError {
+            line: line.number,
+            column: column_at(line, offset),
+            message: message.into(),
+        }
+    }
+
+    /// อ่าน statement จนกว่าจะเจอบรรทัดที่ย่อหน้าไม่เกิน `parent_indent`
+    /// หรือ `<<elseif>>` / `<<else>>` / `<<endif>>` ที่ปิด block นี้
+    fn block(&mut self, parent_indent: Option<usize>) -> Result<Vec<Statement>, ScriptError> {
+        let mut statements = Vec::new();
+        while let Some(line) = self.lines.get(self.index) {
+            if parent_indent.is_some_and(|parent| line.indent <= parent) {
+                break;
+            }
+            if matches!(command_name(line.text), Some("elseif" | "else" | "endif")) {
+                break;
+            }
+            if let Some(statement) = self.statement(parent_indent)? {
+                statements.push(statement);
+            }
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self, parent_indent: Option<usize>) -> Result<Option<Statement>, ScriptError> {
+        let lines = self.lines;
+        let line = &lines[self.index];
+        if line.text.starts_with("->") {
+            return self.options().map(Some);
+        }
+
+        let Some(command) = command_body(line.text) else {
+            self.index += 1;
+            return Ok(Some(parse_dialog_line(line.text)));
+        };
+        let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        let args = args.trim();
+        let words: Vec<&str> = args.split_whitespace().collect();
+        // ตำแหน่งของ argument แรก (หลัง `<<name `)
+        let args_offset = 2 + command.len() - args.len();
+
+        if name == "if" {
+            return self.if_block(parent_indent).map(Some);
+        }
+        self.index += 1;
+
+        let statement = match name {
+            "set" | "declare" => {
+                let action = parse_assignment(args).map_err(|e| self.error(line, args_offset, e))?;
+                if name == "declare" {
+                    self.declarations.push(action);
+                    return Ok(None);
+                }
+                Statement::Action(action)
+            }
+            "jump" => {
+                let [title] = words.as_slice() else {
+                    return Err(self.error(line, args_offset, "<<jump>> expects a node title"));
+                };
+                self.references.push(NodeReference {
+                    title: title.to_string(),
+                    line: line.number,
+                    column: column_at(line, args_offset),
+                });
+                Statement::Jump(title.to_string())
+            }
+            "stop" => Statement::Stop,
+            "bg" => Statement::Background(args.to_string()),
+            "transition" => {
+                let [kind, rest @ ..] = words.as_slice() else {
+                    return Err(self.error(line, args_offset, "<<transition>> expects a type"));
+                };
+                let type_name: TransitionType = serde_json::from_value(serde_json::Value::String(kind.to_string()))
+                    .map_err(|_| self.error(line, args_offset, format!("unknown transition `{}`", kind)))?;
+                let duration = match rest.first() {
+                    Some(raw) => raw.parse::<f32>().map_err(|_| {
+                        self.error(line, args_offset + kind.len() + 1, format!("invalid duration `{}`", raw))
+                    })?,
+                    None => DEFAULT_TRANSITION_DURATION,
+                };
+                Statement::Transition(DialogTransition { type_name, duration })
+            }
+            "show" => {
+                let [character, rest @ ..] = words.as_slice() else {
+                    return Err(self.error(line, args_offset, "<<show>> expects `<character> [position] [expression]`"));
+                };
+                Statement::Show(CharacterState {
+                    name: character.to_string(),
+                    position: rest.first().copied().unwrap_or("center").to_string(),
+                    expression: rest.get(1).copied().unwrap_or("default").to_string(),
+                    highlight: rest.get(2) == Some(&"highlight"),
+                })
+            }
+            "hide" => {
+                let [character] = words.as_slice() else {
+                    return Err(self.error(line, args_offset, "<<hide>> expects a character"));
+                };
+                Statement::Hide(character.to_string())
+            }
+            "character" => parse_character(args).map_err(|e| self.error(line, args_offset, e))?,
+            "elseif" | "else" | "endif" => {
+                return Err(self.error(line, 0, format!("<<{}>> without a matching <<if>>", name)));
+            }
+            _ => {
+                let args = split_args(args)
+                    .map_err(|offset| self.error(line, args_offset + offset, "unterminated quoted argument"))?;
+                let action = DialogAction::from_args(name, &args).map_err(|e| self.error(line, args_offset, e))?;
+                if matches!(&action, DialogAction::Custom { name, .. } if !self.custom_actions.contains(name)) {
+                    return Err(self.error(line, 2, format!("unknown command `{}`", name)));
+                }
+                Statement::Action(action)
+            }
+        };
+        Ok(Some(statement))
+    }
+
+    fn if_block(&mut self, parent_indent: Option<usize>) -> Result<Statement, ScriptError> {
+        let lines = self.lines;
+        let start = &lines[self.index];
+        let mut branches = Vec::new();
+        let mut condition = Some(self.condition(start, "if")?);
+
+        loop {
+            self.index += 1;
+            let body = self.block(parent_indent)?;
+            branches.push((condition, body));
+
+            let Some(line) = lines.get(self.index) else {
+                return Err(self.error(start, 0, "<<if>> without a matching <<endif>>"));
+            };
+            match command_name(line.text) {
+                Some("elseif") if branches.last().is_some_and(|(c, _)| c.is_some()) => {
+                    condition = Some(self.condition(line, "elseif")?);
+                }
+                Some("else") if branches.last().is_some_and(|(c, _)| c.is_some()) => condition = None,
+                Some("endif") => {
+                    self.index += 1;
+                    return Ok(Statement::If(branches));
+                }
+                _ => return Err(self.error(line, 0, format!("unexpected `{}` after <<else>>", line.text))),
+            }
+        }
+    }
+
+    /// เงื่อนไขของ `<<if ...>>` / `<<elseif ...>>`
+    fn condition(&self, line: &Line, keyword: &str) -> Result<Condition, ScriptError> {
+        let command = command_body(line.text).unwrap_or_default();
+        let expression = command[keyword.len()..].trim();
+        let offset = 2 + command.len() - command[keyword.len()..].trim_start().len();
+        if expression.is_empty() {
+            return Err(self.error(line, offset, format!("<<{}>> expects a condition", keyword)));
+        }
+        convert_condition(expression).map_err(|e| self.error(line, offset, e))
+    }
+
+    /// กลุ่ม `->` ที่ย่อหน้าเท่ากันติดกัน เนื้อหาของแต่ละตัวเลือกคือบรรทัดที่ย่อหน้าลึกกว่า
+    fn options(&mut self) -> Result<Statement, ScriptError> {
+        let lines = self.lines;
+        let indent = lines[self.index].indent;
+        let mut options = Vec::new();
+
+        while let Some(line) = lines.get(self.index) {
+            let Some(option) = line.text.strip_prefix("->").filter(|_| line.indent == indent) else {
+                break;
+            };
+            let (text, condition) = match option.rsplit_once("<<") {
+                Some((text, command)) => {
+                    let offset = 2 + text.len() + 2;
+                    let expression = command
+                        .strip_suffix(">>")
+                        .and_then(|c| c.trim().strip_prefix("if"))
+                        .ok_or_else(|| self.error(line, offset, "expected `<<if condition>>` after an option"))?;
+                    let condition = convert_condition(expression.trim()).map_err(|e| self.error(line, offset, e))?;
+                    (text, Some(condition))
+                }
+                None => (option, None),
+            };
+            if text.trim().is_empty() {
+                return Err(self.error(line, 2, "option needs text"));
+            }
+
+            self.index += 1;
+            let body = self.block(Some(indent))?;
+            options.push(YarnOption {
+                text: convert_inline_variables(text.trim()),
+                condition,
+                body,
+            });
+        }
+        Ok(Statement::Options(options))
+    }
+}
+
+impl Compiler {
+    fn compile_node(&mut self, title: String, body: &[Statement]) {
+        self.node_start = self.scene.entries.len();
+        self.pending_label = Some(title);
+        self.terminated = false;
+
+        self.compile_block(body);
+
+        // จบ node โดยไม่มี `<<jump>>` = จบเรื่อง (เหมือน Yarn)
+        if !self.terminated || !self.unbound.is_empty() || self.pending_label.is_some() {
+            self.stop();
+        }
+    }
+
+    fn compile_block(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            match statement {
+                Statement::Line { speaker, text } => {
+                    self.flush_actions();
+                    let character = speaker.clone().unwrap_or_else(|| NARRATOR.to_string());
+                    self.character(&character);
+                    let entry = self.line_entry(character, text.clone());
+                    self.emit(entry);
+                }
+                Statement::Action(action) => self.pending_actions.push(action.clone()),
+                Statement::Background(path) => self.pending_background = Some(path.clone()),
+                Statement::Transition(transition) => self.pending_transition = Some(transition.clone()),
+                Statement::Show(state) => {
+                    self.character(&state.name);
+                    match self.cast.iter_mut().find(|c| c.name == state.name) {
+                        Some(existing) => *existing = state.clone(),
+                        None => self.cast.push(state.clone()),
+                    }
+                }
+                Statement::Hide(name) => self.cast.retain(|c| c.name != *name),
+                Statement::Character { name, display_name, sprite } => {
+                    let character = self.character(name);
+                    if let Some(display_name) = display_name {
+                        character.display_name.insert(DEFAULT_LANGUAGE.to_string(), display_name.clone());
+                    }
+                    if let Some(sprite) = sprite {
+                        character.sprite = sprite.clone();
+                    }
+                }
+                Statement::Jump(title) => {
+                    let mut entry = control_entry(std::mem::take(&mut self.pending_actions));
+                    entry.auto_proceed = Some(JumpTarget::Label(title.clone()));
+                    self.emit(entry);
+                    self.terminated = true;
+                }
+                Statement::Stop => self.stop(),
+                Statement::If(branches) => self.compile_if(branches),
+                Statement::Options(options) => self.compile_options(options),
+            }
+        }
+    }
+
+    /// control entry ที่มี `jump` ต่อ branch ตามลำดับ แล้วให้ทุก branch มาบรรจบกันหลัง `<<endif>>`
+    fn compile_if(&mut self, branches: &[(Option<Condition>, Vec<Statement>)]) {
+        let end = self.new_position();
+        let targets: Vec<usize> = branches.iter().map(|_| self.new_position()).collect();
+
+        let mut actions = std::mem::take(&mut self.pending_actions);
+        let entry_index = self.scene.entries.len();
+        let mut fallback = end;
+        for ((condition, _), target) in branches.iter().zip(&targets) {
+            match condition {
+                Some(condition) => {
+                    actions.push(DialogAction::Jump {
+                        target: JumpTarget::Stage(0),
+                        condition: Some(condition.clone()),
+                    });
+                    self.fixups.push((Fixup::Jump(entry_index, actions.len() - 1), *target));
+                }
+                None => fallback = *target,
+            }
+        }
+        self.emit(control_entry(actions));
+        self.scene.entries[entry_index].auto_proceed = Some(JumpTarget::Stage(0));
+        self.fixups.push((Fixup::AutoProceed(entry_index), fallback));
+        self.terminated = true;
+
+        let cast = self.cast.clone();
+        for ((_, body), target) in branches.iter().zip(targets) {
+            self.cast = cast.clone();
+            self.unbound.push(target);
+            self.compile_block(body);
+            self.goto(end);
+        }
+        self.cast = cast;
+        self.unbound.push(end);
+    }
+
+    /// ผูกตัวเลือกกับบรรทัดล่าสุดของ node นี้ (หรือ entry ว่างถ้าไม่มี)
+    fn compile_options(&mut self, options: &[YarnOption]) {
+        self.flush_actions();
+
+        let attach_to = self
+            .scene
+            .entries
+            .len()
+            .checked_sub(1)
+            .filter(|index| *index >= self.node_start && self.unbound.is_empty() && !self.terminated)
+            .filter(|index| {
+                let entry = &self.scene.entries[*index];
+                !entry.is_control() && entry.actions.is_empty() && entry.auto_proceed.is_none()
+            });
+        let entry_index = match attach_to {
+            Some(index) => index,
+            None => {
+                let entry = self.line_entry(NARRATOR.to_string(), String::new());
+                self.emit(entry)
+            }
+        };
+
+        let end = self.new_position();
+        let mut targets = Vec::new();
+        for option in options {
+            let target = self.new_position();
+            let entry = &mut self.scene.entries[entry_index];
+            entry.choices.push(DialogChoice {
+                text: HashMap::from([(DEFAULT_LANGUAGE.to_string(), option.text.clone())]),
+                target: JumpTarget::Stage(0),
+                conditions: option.condition.iter().cloned().collect(),
+                show_locked: false,
+            });
+            self.fixups.push((Fixup::Choice(entry_index, entry.choices.len() - 1), target));
+            targets.push(target);
+        }
+        self.terminated = true;
+
+        let cast = self.cast.clone();
+        for (option, target) in options.iter().zip(targets) {
+            self.cast = cast.clone();
+            self.unbound.push(target);
+            self.compile_block(&option.body);
+            self.goto(end);
+        }
+        self.unbound.push(end);
+    }
+
+    /// กระโดดไป position ถ้า flow ยังเดินต่อมาถึงจุดนี้ได้
+    fn goto(&mut self, position: usize) {
+        if self.terminated && self.unbound.is_empty() && self.pending_actions.is_empty() {
+            return;
+        }
+        let actions = std::mem::take(&mut self.pending_actions);
+        let entry_index = self.emit(control_entry(actions));
+        self.scene.entries[entry_index].auto_proceed = Some(JumpTarget::Stage(0));
+        self.fixups.push((Fixup::AutoProceed(entry_index), position));
+        self.terminated = true;
+    }
+
+    fn stop(&mut self) {
+        let mut actions = std::mem::take(&mut self.pending_actions);
+        actions.push(DialogAction::End);
+        self.emit(control_entry(actions));
+        self.terminated = true;
+    }
+
+    fn flush_actions(&mut self) {
+        if !self.pending_actions.is_empty() {
+            let actions = std::mem::take(&mut self.pending_actions);
+            self.emit(control_entry(actions));
+        }
+    }
+
+    fn new_position(&mut self) -> usize {
+        self.positions.push(None);
+        self.positions.len() - 1
+    }
+
+    fn line_entry(&mut self, character: String, text: String) -> DialogEntry {
+        DialogEntry {
+            label: None,
+            character,
+            text: HashMap::from([(DEFAULT_LANGUAGE.to_string(), text)]),
+            actions: Vec::new(),
+            choices: Vec::new(),
+            auto_proceed: None,
+            character_states: self.cast.clone(),
+            background: self.pending_background.take(),
+            transition: self.pending_transition.take(),
+        }
+    }
+
+    fn emit(&mut self, mut entry: DialogEntry) -> usize {
+        let index = self.scene.entries.len();
+        if let Some(label) = self.pending_label.take() {
+            entry.label = Some(label);
+        }
+        for position in self.unbound.drain(..) {
+            self.positions[position].get_or_insert(index);
+        }
+        self.scene.entries.push(entry);
+        self.terminated = false;
+        index
+    }
+
+    fn apply_fixups(&mut self) {
+        for (fixup, position) in std::mem::take(&mut self.fixups) {
+            // ทุก position ถูกผูกก่อนจบ node เสมอ (`compile_node` ปิดท้ายด้วย entry)
+            let target = JumpTarget::Stage(self.positions[position].unwrap_or_default());
+            match fixup {
+                Fixup::AutoProceed(entry) => self.scene.entries[entry].auto_proceed = Some(target),
+                Fixup::Choice(entry, choice) => self.scene.entries[entry].choices[choice].target = target,
+                Fixup::Jump(entry, action) => {
+                    if let DialogAction::Jump { target: jump, .. } = &mut self.scene.entries[entry].actions[action] {
+                        *jump = target;
+                    }
+                }
+            }
+        }
+    }
+
+    fn character(&mut self, name: &str) -> &mut DialogCharacter {
+        let index = match self.scene.characters.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.scene.characters.push(DialogCharacter {
+                    name: name.to_string(),
+                    display_name: HashMap::new(),
+                    sprite: String::new(),
+                    positions: HashMap::new(),
+                });
+                self.scene.characters.len() - 1
+            }
+        };
+        &mut self.scene.characters[index]
+    }
+}
+
+fn control_entry(actions: Vec<DialogAction>) -> DialogEntry {
+    DialogEntry {
+        label: None,
+        character: String::new(),
+        text: HashMap::new(),
+        actions,
+        choices: Vec::new(),
+        auto_proceed: None,
+        character_states: Vec::new(),
+        background: None,
+        transition: None,
+    }
+}
+
+/// `Miki: text` หรือบรรทัดบรรยาย
+fn parse_dialog_line(text: &str) -> Statement {
+    match text.split_once(':') {
+        Some((speaker, rest)) if is_identifier(speaker.trim()) => Statement::Line {
+            speaker: Some(speaker.trim().to_string()),
+            text: convert_inline_variables(rest.trim()),
+        },
+        _ => Statement::Line {
+            speaker: None,
+            text: convert_inline_variables(text),
+        },
+    }
+}
+
+/// `{$gold}` ในข้อความกลายเป็น placeholder `{var:gold}` ที่แทนค่าตอนแสดงผล
+fn convert_inline_variables(text: &str) -> String {
+    text.replace("{$", "{var:")
+}
+
+/// `Miki "Miki" sprite=characters/miki.png`
+fn parse_character(args: &str) -> Result<Statement, String> {
+    let (name, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    if name.is_empty() {
+        return Err("<<character>> expects a name".to_string());
+    }
+
+    let mut rest = rest.trim_start();
+    let mut display_name = None;
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let end = quoted.find('"').ok_or("unterminated display name")?;
+        display_name = Some(quoted[..end].to_string());
+        rest = &quoted[end + 1..];
+    }
+
+    let mut sprite = None;
+    for option in rest.split_whitespace() {
+        match option.split_once('=') {
+            Some(("sprite", path)) => sprite = Some(path.to_string()),
+            _ => return Err(format!("unknown character option `{}`", option)),
+        }
+    }
+    Ok(Statement::Character {
+        name: name.to_string(),
+        display_name,
+        sprite,
+    })
+}
+
+/// `$x to 1`, `$x = "name"`, `$x = $x + 1`, `$x += 1`
+fn parse_assignment(args: &str) -> Result<DialogAction, String> {
+    let variable = args
+        .strip_prefix('$')
+        .ok_or_else(|| format!("expected a `$variable`, got `{}`", args))?;
+    let end = variable
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(variable.len());
+    let (name, rest) = variable.split_at(end);
+    if name.is_empty() {
+        return Err("expected a variable name after `$`".to_string());
+    }
+    let rest = rest.trim_start();
+
+    let parse_amount = |raw: &str| {
+        raw.trim()
+            .parse::<i64>()
+            .map_err(|_| format!("expected an integer, got `{}`", raw.trim()))
+    };
+    if let Some(amount) = rest.strip_prefix("+=") {
+        return Ok(DialogAction::AddVar { name: name.to_string(), amount: parse_amount(amount)? });
+    }
+    if let Some(amount) = rest.strip_prefix("-=") {
+        return Ok(DialogAction::AddVar { name: name.to_string(), amount: -parse_amount(amount)? });
+    }
+
+    let value = rest
+        .strip_prefix("to ")
+        .or_else(|| rest.strip_prefix('='))
+        .ok_or_else(|| format!("expected `to` or `=` after `${}`", name))?
+        .trim();
+
+    // `$x + n` / `$x - n` ของตัวแปรเดียวกัน
+    if let Some(offset) = value.strip_prefix('$').and_then(|v| v.strip_prefix(name)) {
+        let offset = offset.trim_start();
+        if let Some(amount) = offset.strip_prefix('+') {
+            return Ok(DialogAction::AddVar { name: name.to_string(), amount: parse_amount(amount)? });
+        }
+        if let Some(amount) = offset.strip_prefix('-') {
+            return Ok(DialogAction::AddVar { name: name.to_string(), amount: -parse_amount(amount)? });
+        }
+    }
+
+    let literal = (value.len() >= 2 && value.starts_with('"') && value.ends_with('"'))
+        || matches!(value, "true" | "false")
+        || value.parse::<i64>().is_ok();
+    if !literal {
+        return Err(format!("only literal values and `${} + n` are supported, got `{}`", name, value));
+    }
+    Ok(DialogAction::SetVar { name: name.to_string(), value: StoryValue::parse(value) })
+}
+
+/// แปลงเงื่อนไขของ Yarn (`$x is 3 and not $met`) เป็น `Condition`
+fn convert_condition(expression: &str) -> Result<Condition, String> {
+    let mut converted = String::new();
+    let mut word = String::new();
+    let mut in_string = false;
+
+    let flush = |word: &mut String, converted: &mut String| -> Result<(), String> {
+        let replacement = match word.as_str() {
+            "is" | "eq" => "==",
+            "neq" => "!=",
+            "gt" => ">",
+            "lt" => "<",
+            "gte" => ">=",
+            "lte" => "<=",
+            "xor" => return Err("`xor` is not supported".to_string()),
+            other => other,
+        };
+        converted.push_str(replacement);
+        word.clear();
+        Ok(())
+    };
+
+    for c in expression.chars() {
+        if in_string {
+            converted.push(c);
+            in_string = c != '"';
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut converted)?;
+            match c {
+                '$' => {}
+                '"' => {
+                    converted.push(c);
+                    in_string = true;
+                }
+                _ => converted.push(c),
+            }
+        }
+    }
+    flush(&mut word, &mut converted)?;
+
+    Condition::parse(&converted).map_err(|e| format!("invalid condition: {}", e.message))
+}
+
+/// `<<name args>>` -> `name args`
+fn command_body(text: &str) -> Option<&str> {
+    text.strip_prefix("<<")?.strip_suffix(">>").map(str::trim)
+}
+
+fn command_name(text: &str) -> Option<&str> {
+    command_body(text)?.split_whitespace().next()
+}
+
+/// ตัด `// comment` และ `#tag` ท้ายบรรทัด (ไม่นับที่อยู่ใน `"..."`)
+fn strip_comment(text: &str) -> &str {
+    let mut in_string = false;
+    let mut previous = ' ';
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '/' if !in_string && text[index..].starts_with("//") && previous.is_whitespace() => {
+                return &text[..index];
+            }
+            '#' if !in_string && previous.is_whitespace() => return &text[..index],
+            _ => {}
+        }
+        previous = c;
+    }
+    text
+}
+
+/// tab นับเป็น 4 ช่อง
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "title: Start
+---
+<<declare $gold = 5>>
+Miki: You have {$gold} coins. #line:shop_01
+-> Buy <<if $gold >= 3>>
+    <<set $gold -= 3>>
+-> Leave
+<<jump End>>
+===
+title: End
+---
+Bye.
+===
+";
+
+    fn import(source: &str) -> Result<DialogScene, ScriptError> {
+        import_yarn(source, &HashSet::new())
+    }
+
+    fn error_at(source: &str) -> (usize, usize, String) {
+        let error = import(source).unwrap_err();
+        (error.line, error.column, error.message)
+    }
+
+    #[test]
+    fn imports_nodes_options_and_commands() {
+        let scene = import(SOURCE).unwrap();
+        assert_eq!(scene.entries[0].actions, vec![DialogAction::SetVar { name: "gold".to_string(), value: StoryValue::Int(5) }]);
+
+        let start = scene.label_index("Start").unwrap();
+        let line = &scene.entries[start];
+        assert_eq!(line.character, "Miki");
+        assert_eq!(line.text["english"], "You have {var:gold} coins.");
+        assert_eq!(line.choices.len(), 2);
+        assert_eq!(line.choices[0].conditions, vec![Condition::parse("gold >= 3").unwrap()]);
+        assert!(line.choices[1].conditions.is_empty());
+
+        let JumpTarget::Stage(buy) = line.choices[0].target else {
+            panic!("option target should be a stage");
+        };
+        assert_eq!(scene.entries[buy].actions, vec![DialogAction::AddVar { name: "gold".to_string(), amount: -3 }]);
+
+        let end = scene.label_index("End").unwrap();
+        assert_eq!(scene.entries[end].text["english"], "Bye.");
+        assert_eq!(scene.entries.last().unwrap().actions, vec![DialogAction::End]);
+    }
+
+    #[test]
+    fn error_columns_count_characters() {
+        // `if` อยู่ตัวอักษรที่ 12 (byte ที่ 21 เพราะภาษาไทยตัวละ 3 byte)
+        assert_eq!(
+            error_at("title: Start\n---\n-> ไปไหน <<if $a >=>>\n===\n"),
+            (3, 12, "invalid condition: unexpected end of condition".to_string())
+        );
+        assert_eq!(error_at("title: Start\n---\nHi\n<<jump Nowhere>>\n===\n").1, 8);
+    }
+
+    #[test]
+    fn command_errors_point_at_the_argument() {
+        assert_eq!(
+            error_at("title: Start\n---\nHi\n<<play_bgm \"a b.ogg>>\n===\n"),
+            (4, 12, "unterminated quoted argument".to_string())
+        );
+        assert_eq!(error_at("title: Start\n---\nHi\n<<wait -1>>\n===\n").1, 8);
+    }
+}
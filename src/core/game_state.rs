@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use crate::core::input::action::{ActionState, InputAction};
 
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GameState {
@@ -7,6 +8,12 @@ pub enum GameState {
     Paused,
     Settings,
     Loading,
+    /// หน้า save/load (โหมดอยู่ใน `SaveLoadMode`)
+    SaveLoad,
+    /// backlog แสดงทับเกมเหมือน Paused
+    Backlog,
+    /// หน้าตั้งค่าปุ่ม เปิดจาก Settings
+    KeyBindings,
 }
 
 impl Default for GameState {
@@ -32,8 +39,10 @@ pub fn handle_state_changes(
     current_state: Res<State<GameState>>,
 ) {
     for event in events.read() {
-        // เก็บ previous state เฉพาะเมื่อไป Paused หรือ Settings
-        if event.new_state == GameState::Paused || event.new_state == GameState::Settings {
+        // เก็บ previous state เฉพาะเมื่อไป Paused, Settings หรือ SaveLoad (กลับจากหน้าตั้งค่าปุ่มไม่นับ)
+        if matches!(event.new_state, GameState::Paused | GameState::Settings | GameState::SaveLoad)
+            && *current_state.get() != GameState::KeyBindings
+        {
             previous_state.state = Some(current_state.get().clone());
         }
         next_state.set(event.new_state.clone());
@@ -41,25 +50,25 @@ pub fn handle_state_changes(
 }
 
 pub fn handle_pause_input(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     current_state: Res<State<GameState>>,
     mut change_events: EventWriter<ChangeStateEvent>,
     previous_state: Res<PreviousState>,
 ) {
-    if keyboard.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(InputAction::Back) {
         match current_state.get() {
             GameState::InGame => {
                 change_events.send(ChangeStateEvent {
                     new_state: GameState::Paused,
                 });
             }
-            GameState::Paused => {
+            GameState::Paused | GameState::Backlog => {
                 // กลับไป InGame เสมอ (ไม่ใช้ previous_state)
                 change_events.send(ChangeStateEvent {
                     new_state: GameState::InGame,
                 });
             }
-            GameState::Settings => {
+            GameState::Settings | GameState::SaveLoad => {
                 // กลับไป state ก่อนหน้า หรือ InGame ถ้าไม่มี
                 if let Some(prev_state) = &previous_state.state {
                     change_events.send(ChangeStateEvent {
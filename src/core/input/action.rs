@@ -0,0 +1,275 @@
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use crate::core::input::binding::InputBinding;
+use crate::core::resources::SettingsResource;
+
+/// จำนวนตัวเลือกที่เลือกด้วยปุ่มได้ (`Choice(1)` ถึง `Choice(9)`)
+pub const CHOICE_ACTIONS: u8 = 9;
+
+/// คำสั่งของผู้เล่น ระบบต่างๆ อ่าน action แทนปุ่มโดยตรง
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum InputAction {
+    /// ไปบรรทัดถัดไป / แสดงข้อความทั้งหมด
+    Advance,
+    /// pause หรือปิดหน้าที่เปิดอยู่
+    Back,
+    /// ข้ามข้อความขณะกดค้าง
+    Skip,
+    /// เปิด/ปิดโหมดข้ามข้อความ
+    SkipToggle,
+    Auto,
+    Backlog,
+    Rollback,
+    QuickSave,
+    QuickLoad,
+    /// ซ่อน/แสดงกล่องข้อความ
+    HideUi,
+    /// ย้าย focus ของปุ่มในเมนูและตัวเลือก
+    NavigateUp,
+    NavigateDown,
+    NavigateLeft,
+    NavigateRight,
+    /// ย้าย focus ไปปุ่มถัดไป / ก่อนหน้าตามลำดับบนจอ
+    FocusNext,
+    FocusPrev,
+    /// เลือกตัวเลือกที่ N (เริ่มที่ 1)
+    Choice(u8),
+}
+
+impl InputAction {
+    /// ทุก action ตามลำดับในหน้าตั้งค่าปุ่ม
+    pub fn all() -> Vec<InputAction> {
+        let mut actions = vec![
+            InputAction::Advance,
+            InputAction::Back,
+            InputAction::Skip,
+            InputAction::SkipToggle,
+            InputAction::Auto,
+            InputAction::Backlog,
+            InputAction::Rollback,
+            InputAction::QuickSave,
+            InputAction::QuickLoad,
+            InputAction::HideUi,
+            InputAction::NavigateUp,
+            InputAction::NavigateDown,
+            InputAction::NavigateLeft,
+            InputAction::NavigateRight,
+            InputAction::FocusNext,
+            InputAction::FocusPrev,
+        ];
+        actions.extend((1..=CHOICE_ACTIONS).map(InputAction::Choice));
+        actions
+    }
+
+    /// key ของชื่อ action ใน language pack (`Choice` ใช้ชื่อเดียวกันต่อท้ายด้วยเลข)
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            InputAction::Advance => "ui.input_advance",
+            InputAction::Back => "ui.input_back",
+            InputAction::Skip => "ui.input_skip",
+            InputAction::SkipToggle => "ui.input_skip_toggle",
+            InputAction::Auto => "ui.input_auto",
+            InputAction::Backlog => "ui.input_backlog",
+            InputAction::Rollback => "ui.input_rollback",
+            InputAction::QuickSave => "ui.input_quick_save",
+            InputAction::QuickLoad => "ui.input_quick_load",
+            InputAction::HideUi => "ui.input_hide_ui",
+            InputAction::NavigateUp => "ui.input_navigate_up",
+            InputAction::NavigateDown => "ui.input_navigate_down",
+            InputAction::NavigateLeft => "ui.input_navigate_left",
+            InputAction::NavigateRight => "ui.input_navigate_right",
+            InputAction::FocusNext => "ui.input_focus_next",
+            InputAction::FocusPrev => "ui.input_focus_prev",
+            InputAction::Choice(_) => "ui.input_choice",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<InputBinding> {
+        use InputBinding::{Gamepad, Key, Mouse};
+        match self {
+            InputAction::Advance => vec![
+                Mouse(MouseButton::Left), Key(KeyCode::Space), Key(KeyCode::Enter), Gamepad(GamepadButtonType::South),
+            ],
+            // Start เปิดเมนู pause ระหว่างเล่นเหมือน ESC
+            InputAction::Back => vec![
+                Key(KeyCode::Escape), Mouse(MouseButton::Right),
+                Gamepad(GamepadButtonType::East), Gamepad(GamepadButtonType::Start),
+            ],
+            InputAction::Skip => vec![
+                Key(KeyCode::ControlLeft), Key(KeyCode::ControlRight), Gamepad(GamepadButtonType::RightTrigger2),
+            ],
+            InputAction::SkipToggle => vec![Key(KeyCode::KeyS)],
+            InputAction::Auto => vec![Key(KeyCode::KeyA), Gamepad(GamepadButtonType::North)],
+            InputAction::Backlog => vec![Key(KeyCode::KeyH), Gamepad(GamepadButtonType::West)],
+            InputAction::Rollback => vec![
+                InputBinding::WheelUp, Key(KeyCode::PageUp), Gamepad(GamepadButtonType::LeftTrigger),
+            ],
+            InputAction::QuickSave => vec![Key(KeyCode::F5)],
+            InputAction::QuickLoad => vec![Key(KeyCode::F9)],
+            InputAction::HideUi => vec![Key(KeyCode::KeyV), Gamepad(GamepadButtonType::Select)],
+            InputAction::NavigateUp => vec![Key(KeyCode::ArrowUp), Gamepad(GamepadButtonType::DPadUp)],
+            InputAction::NavigateDown => vec![Key(KeyCode::ArrowDown), Gamepad(GamepadButtonType::DPadDown)],
+            InputAction::NavigateLeft => vec![Key(KeyCode::ArrowLeft), Gamepad(GamepadButtonType::DPadLeft)],
+            InputAction::NavigateRight => vec![Key(KeyCode::ArrowRight), Gamepad(GamepadButtonType::DPadRight)],
+            InputAction::FocusNext => vec![Key(KeyCode::Tab)],
+            // ไม่มีปุ่มเริ่มต้น (ลูกศรขึ้นย้อนกลับได้อยู่แล้ว) ผู้เล่นตั้งเองได้ในหน้าตั้งค่าปุ่ม
+            InputAction::FocusPrev => Vec::new(),
+            InputAction::Choice(number) => {
+                const DIGITS: [KeyCode; 9] = [
+                    KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
+                    KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
+                    KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+                ];
+                DIGITS
+                    .get(usize::from(*number).wrapping_sub(1))
+                    .map(|key| vec![Key(*key)])
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+impl fmt::Display for InputAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputAction::Choice(number) => write!(f, "Choice{}", number),
+            action => write!(f, "{:?}", action),
+        }
+    }
+}
+
+impl From<InputAction> for String {
+    fn from(action: InputAction) -> Self {
+        action.to_string()
+    }
+}
+
+impl TryFrom<String> for InputAction {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        InputAction::all()
+            .into_iter()
+            .find(|action| action.to_string() == name)
+            .ok_or_else(|| format!("Unknown input action `{}`", name))
+    }
+}
+
+/// ตารางปุ่มของแต่ละ action (บันทึกในไฟล์ settings)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: BTreeMap<InputAction, Vec<InputBinding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            bindings: InputAction::all()
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: InputAction) -> &[InputBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// ตั้งปุ่มให้ action แทนปุ่มเดิมของอุปกรณ์ชนิดเดียวกัน (คีย์บอร์ด/เมาส์ หรือ gamepad)
+    /// และเอาปุ่มนี้ออกจาก action อื่น (หนึ่งปุ่มหนึ่งคำสั่ง)
+    pub fn rebind(&mut self, action: InputAction, binding: InputBinding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+        bindings.sort_by_key(InputBinding::is_gamepad);
+    }
+
+    /// เติม action ที่ไม่มีในไฟล์ (เช่น action ที่เพิ่มใน version ใหม่) ด้วยปุ่มเริ่มต้น
+    /// ยกเว้นปุ่มที่ action อื่นใช้อยู่แล้ว (หนึ่งปุ่มหนึ่งคำสั่ง)
+    pub fn fill_missing(&mut self) {
+        for action in InputAction::all() {
+            if self.bindings.contains_key(&action) {
+                continue;
+            }
+            let defaults = action
+                .default_bindings()
+                .into_iter()
+                .filter(|binding| !self.bindings.values().any(|bindings| bindings.contains(binding)))
+                .collect();
+            self.bindings.insert(action, defaults);
+        }
+    }
+}
+
+/// action ที่เกิดใน frame นี้ คำนวณจาก `InputMap` ใน `PreUpdate`
+///
+/// อ่านจาก resource `ButtonInput` ของคีย์บอร์ด เมาส์ และ gamepad เท่านั้น จึงทดสอบใน `App` แบบ headless
+/// ได้ด้วยการกดปุ่มปลอมใน `ButtonInput<GamepadButton>`
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+pub fn update_action_state(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    touch: Res<Touches>,
+    settings: Res<SettingsResource>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let (mut wheel_up, mut wheel_down) = (false, false);
+    for event in mouse_wheel.read() {
+        wheel_up |= event.y > 0.0;
+        wheel_down |= event.y < 0.0;
+    }
+
+    action_state.pressed.clear();
+    action_state.just_pressed.clear();
+    for action in InputAction::all() {
+        for binding in settings.bindings.bindings(action) {
+            let (pressed, just_pressed) = match binding {
+                InputBinding::Key(key) => (keyboard.pressed(*key), keyboard.just_pressed(*key)),
+                InputBinding::Mouse(button) => (mouse.pressed(*button), mouse.just_pressed(*button)),
+                InputBinding::WheelUp => (wheel_up, wheel_up),
+                InputBinding::WheelDown => (wheel_down, wheel_down),
+                InputBinding::Gamepad(button_type) => (
+                    gamepad_buttons.get_pressed().any(|button| button.button_type == *button_type),
+                    gamepad_buttons.get_just_pressed().any(|button| button.button_type == *button_type),
+                ),
+            };
+            if pressed {
+                action_state.pressed.insert(action);
+            }
+            if just_pressed {
+                action_state.just_pressed.insert(action);
+            }
+        }
+    }
+
+    // แตะจอไปต่อเสมอ (ตั้งค่าไม่ได้)
+    if touch.iter_just_pressed().next().is_some() {
+        action_state.just_pressed.insert(InputAction::Advance);
+    }
+}
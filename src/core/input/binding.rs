@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// ปุ่มที่ผูกกับ action ได้ เก็บในไฟล์ settings เป็นชื่อ เช่น `"KeyA"`, `"MouseRight"`, `"WheelUp"`, `"GamepadSouth"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    WheelUp,
+    WheelDown,
+    /// ปุ่มของ gamepad ตัวไหนก็ได้
+    Gamepad(GamepadButtonType),
+}
+
+/// ปุ่มคีย์บอร์ดที่ตั้งได้ในหน้าตั้งค่าปุ่ม (ชื่อในไฟล์คือชื่อ variant ของ `KeyCode`)
+pub const BINDABLE_KEYS: [KeyCode; 91] = [
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight,
+    KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End, KeyCode::Insert, KeyCode::Delete,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft, KeyCode::AltRight,
+    KeyCode::Backquote, KeyCode::Minus, KeyCode::Equal, KeyCode::BracketLeft, KeyCode::BracketRight,
+    KeyCode::Backslash, KeyCode::Semicolon, KeyCode::Quote, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::NumpadEnter,
+];
+
+/// ปุ่ม gamepad ที่ตั้งได้ (ชื่อในไฟล์คือ `Gamepad` ต่อด้วยชื่อ variant ของ `GamepadButtonType`)
+pub const BINDABLE_GAMEPAD_BUTTONS: [GamepadButtonType; 19] = [
+    GamepadButtonType::South, GamepadButtonType::East, GamepadButtonType::North, GamepadButtonType::West,
+    GamepadButtonType::C, GamepadButtonType::Z,
+    GamepadButtonType::LeftTrigger, GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger, GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select, GamepadButtonType::Start, GamepadButtonType::Mode,
+    GamepadButtonType::LeftThumb, GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp, GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft, GamepadButtonType::DPadRight,
+];
+
+const MOUSE_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+impl InputBinding {
+    /// ปุ่มของ gamepad (ที่เหลือคือคีย์บอร์ดและเมาส์)
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, InputBinding::Gamepad(_))
+    }
+
+    /// ชื่อสั้นสำหรับแสดงในหน้าตั้งค่าปุ่ม เช่น `A`, `1`, `Ctrl L`, `Mouse Right`, `Pad A`
+    /// (ปุ่ม gamepad ใช้ชื่อแบบจอย Xbox)
+    pub fn label(&self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{:?}", key);
+                let short = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                short
+                    .replace("Control", "Ctrl")
+                    .replace("Left", " L")
+                    .replace("Right", " R")
+            }
+            InputBinding::Mouse(button) => format!("Mouse {:?}", button),
+            InputBinding::WheelUp => "Wheel Up".to_string(),
+            InputBinding::WheelDown => "Wheel Down".to_string(),
+            InputBinding::Gamepad(button) => match button {
+                GamepadButtonType::South => "Pad A".to_string(),
+                GamepadButtonType::East => "Pad B".to_string(),
+                GamepadButtonType::North => "Pad Y".to_string(),
+                GamepadButtonType::West => "Pad X".to_string(),
+                GamepadButtonType::LeftTrigger => "LB".to_string(),
+                GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+                GamepadButtonType::RightTrigger => "RB".to_string(),
+                GamepadButtonType::RightTrigger2 => "RT".to_string(),
+                GamepadButtonType::LeftThumb => "LS".to_string(),
+                GamepadButtonType::RightThumb => "RS".to_string(),
+                GamepadButtonType::DPadUp => "D-Pad Up".to_string(),
+                GamepadButtonType::DPadDown => "D-Pad Down".to_string(),
+                GamepadButtonType::DPadLeft => "D-Pad Left".to_string(),
+                GamepadButtonType::DPadRight => "D-Pad Right".to_string(),
+                other => format!("Pad {:?}", other),
+            },
+        }
+    }
+}
+
+impl fmt::Display for InputBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputBinding::Key(key) => write!(f, "{:?}", key),
+            InputBinding::Mouse(button) => write!(f, "Mouse{:?}", button),
+            InputBinding::WheelUp => f.write_str("WheelUp"),
+            InputBinding::WheelDown => f.write_str("WheelDown"),
+            InputBinding::Gamepad(button) => write!(f, "Gamepad{:?}", button),
+        }
+    }
+}
+
+impl From<InputBinding> for String {
+    fn from(binding: InputBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for InputBinding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.as_str() {
+            "WheelUp" => return Ok(InputBinding::WheelUp),
+            "WheelDown" => return Ok(InputBinding::WheelDown),
+            _ => {}
+        }
+        BINDABLE_KEYS
+            .iter()
+            .map(|key| InputBinding::Key(*key))
+            .chain(MOUSE_BUTTONS.iter().map(|button| InputBinding::Mouse(*button)))
+            .chain(BINDABLE_GAMEPAD_BUTTONS.iter().map(|button| InputBinding::Gamepad(*button)))
+            .find(|binding| binding.to_string() == name)
+            .ok_or_else(|| format!("Unknown input binding `{}`", name))
+    }
+}
@@ -0,0 +1,2 @@
+pub mod action;
+pub mod binding;
\ No newline at end of file
@@ -182,9 +182,49 @@ fn get_text_from_pack(pack: &LanguagePack, path: &str) -> String {
         ["ui", "main_menu"] => pack.ui.main_menu.clone(),
         ["ui", "pause_controls_hint"] => pack.ui.pause_controls_hint.clone(),
 
+        // สำหรับ Save/Load
+        ["ui", "save_game"] => pack.ui.save_game.clone(),
+        ["ui", "load_game"] => pack.ui.load_game.clone(),
+        ["ui", "slot"] => pack.ui.slot.clone(),
+        ["ui", "empty_slot"] => pack.ui.empty_slot.clone(),
+        ["ui", "quick_save"] => pack.ui.quick_save.clone(),
+        ["ui", "auto_save"] => pack.ui.auto_save.clone(),
+
+        // สำหรับ Backlog
+        ["ui", "backlog_title"] => pack.ui.backlog_title.clone(),
+        ["ui", "backlog_hint"] => pack.ui.backlog_hint.clone(),
+        ["ui", "skip_unread_setting"] => pack.ui.skip_unread_setting.clone(),
+        ["ui", "mark_unread_setting"] => pack.ui.mark_unread_setting.clone(),
+        ["ui", "auto_speed_setting"] => pack.ui.auto_speed_setting.clone(),
+        ["ui", "controls_setting"] => pack.ui.controls_setting.clone(),
+        ["ui", "key_bindings_title"] => pack.ui.key_bindings_title.clone(),
+        ["ui", "key_bindings_hint"] => pack.ui.key_bindings_hint.clone(),
+        ["ui", "press_key"] => pack.ui.press_key.clone(),
+        ["ui", "reset_defaults"] => pack.ui.reset_defaults.clone(),
+        ["ui", "input_advance"] => pack.ui.input_advance.clone(),
+        ["ui", "input_back"] => pack.ui.input_back.clone(),
+        ["ui", "input_skip"] => pack.ui.input_skip.clone(),
+        ["ui", "input_skip_toggle"] => pack.ui.input_skip_toggle.clone(),
+        ["ui", "input_auto"] => pack.ui.input_auto.clone(),
+        ["ui", "input_backlog"] => pack.ui.input_backlog.clone(),
+        ["ui", "input_rollback"] => pack.ui.input_rollback.clone(),
+        ["ui", "input_quick_save"] => pack.ui.input_quick_save.clone(),
+        ["ui", "input_quick_load"] => pack.ui.input_quick_load.clone(),
+        ["ui", "input_hide_ui"] => pack.ui.input_hide_ui.clone(),
+        ["ui", "input_choice"] => pack.ui.input_choice.clone(),
+        ["ui", "input_navigate_up"] => pack.ui.input_navigate_up.clone(),
+        ["ui", "input_navigate_down"] => pack.ui.input_navigate_down.clone(),
+        ["ui", "input_navigate_left"] => pack.ui.input_navigate_left.clone(),
+        ["ui", "input_navigate_right"] => pack.ui.input_navigate_right.clone(),
+        ["ui", "input_focus_next"] => pack.ui.input_focus_next.clone(),
+        ["ui", "input_focus_prev"] => pack.ui.input_focus_prev.clone(),
+
         ["dialog", "choose_action"] => pack.dialog.choose_action.clone(),
         ["dialog", "continue_hint"] => pack.dialog.continue_hint.clone(),
         ["dialog", "language_indicator"] => pack.dialog.language_indicator.clone(),
+        ["dialog", "skip_indicator"] => pack.dialog.skip_indicator.clone(),
+        ["dialog", "unread_indicator"] => pack.dialog.unread_indicator.clone(),
+        ["dialog", "auto_indicator"] => pack.dialog.auto_indicator.clone(),
 
         ["game", "narrator"] => pack.game.narrator.clone(),
         ["game", "you"] => pack.game.you.clone(),
@@ -38,6 +38,39 @@ pub struct UiTexts {
     pub resume_game: String,
     pub main_menu: String,
     pub pause_controls_hint: String,
+    pub save_game: String,
+    pub load_game: String,
+    pub slot: String,
+    pub empty_slot: String,
+    pub quick_save: String,
+    pub auto_save: String,
+    pub backlog_title: String,
+    pub backlog_hint: String,
+    pub skip_unread_setting: String,
+    pub mark_unread_setting: String,
+    pub auto_speed_setting: String,
+    pub controls_setting: String,
+    pub key_bindings_title: String,
+    pub key_bindings_hint: String,
+    pub press_key: String,
+    pub reset_defaults: String,
+    pub input_advance: String,
+    pub input_back: String,
+    pub input_skip: String,
+    pub input_skip_toggle: String,
+    pub input_auto: String,
+    pub input_backlog: String,
+    pub input_rollback: String,
+    pub input_quick_save: String,
+    pub input_quick_load: String,
+    pub input_hide_ui: String,
+    pub input_choice: String,
+    pub input_navigate_up: String,
+    pub input_navigate_down: String,
+    pub input_navigate_left: String,
+    pub input_navigate_right: String,
+    pub input_focus_next: String,
+    pub input_focus_prev: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -45,6 +78,9 @@ pub struct DialogTexts {
     pub choose_action: String,
     pub continue_hint: String,
     pub language_indicator: String,
+    pub skip_indicator: String,
+    pub unread_indicator: String,
+    pub auto_indicator: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -63,6 +99,18 @@ pub enum LanguageCode {
 }
 
 impl LanguageCode {
+    /// ทุกภาษาที่เกมรองรับ
+    pub const ALL: [LanguageCode; 3] = [LanguageCode::Thai, LanguageCode::English, LanguageCode::Japanese];
+
+    /// key ของภาษาใน `DialogEntry.text` / `DialogChoice.text` (เช่น `"thai"`)
+    pub fn dialog_key(&self) -> &'static str {
+        match self {
+            LanguageCode::Thai => "thai",
+            LanguageCode::English => "english",
+            LanguageCode::Japanese => "japanese",
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             LanguageCode::Thai => "th-th",
@@ -87,6 +135,17 @@ impl LanguageCode {
             LanguageCode::Japanese => "日本語",
         }
     }
+
+    /// เวลาอ่านต่อหนึ่ง grapheme (วินาที) ใช้คำนวณเวลารอของโหมด auto
+    ///
+    /// ภาษาญี่ปุ่นหนึ่งตัวอักษรมีความหมายมากกว่าตัวอักษรละติน จึงใช้เวลาอ่านนานกว่า
+    pub fn reading_time_per_grapheme(&self) -> f32 {
+        match self {
+            LanguageCode::Thai => 0.05,
+            LanguageCode::English => 0.04,
+            LanguageCode::Japanese => 0.1,
+        }
+    }
 }
 
 /// Language pack asset loader
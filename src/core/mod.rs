@@ -4,4 +4,8 @@ pub mod resources;
 pub mod game_state;
 pub mod plugin;
 pub mod language;
-pub mod text;
\ No newline at end of file
+pub mod text;
+pub mod story;
+pub mod validate;
+pub mod save;
+pub mod input;
\ No newline at end of file
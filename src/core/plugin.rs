@@ -2,13 +2,34 @@ use bevy::prelude::*;
 use crate::core::resources::*;
 use crate::core::game_state::{GameState, ChangeStateEvent, PreviousState, handle_state_changes, handle_pause_input};
 use crate::core::dialog::{
-    manager::{load_dialogs, manage_dialog_state},
+    manager::{
+        load_story_manifest, register_story_scenes, preload_scene_assets, manage_dialog_state,
+        reload_modified_scenes, check_scene_links,
+    },
     choice::{ChoiceState, handle_choice_selection as core_handle_choice_selection},
+    rollback::{RollbackEvent, record_history_step, handle_rollback},
+    backlog::Backlog,
+    skip::{SkipState, handle_skip_input, update_skip},
+    auto::{AutoState, handle_auto_input, update_auto},
+    action::{
+        ActionHandler, ActionRegistry, ActionWait, DialogActionEvent, PendingCustomActions, ScreenShake,
+        handle_dialog_actions, run_custom_actions, tick_action_wait, apply_screen_shake,
+    },
 };
 use crate::core::scene::{
     background::{setup_scene_background, update_background},
     character::{setup_characters, update_characters, check_character_assets},
+    transition::{TransitionState, start_entry_transition, update_transitions},
 };
+use crate::core::story::variables::StoryVariables;
+use crate::core::input::action::{ActionState, update_action_state};
+use crate::core::save::manager::{
+    AutoSaveRotation, PlayTime, NewGameEvent, SaveGameEvent, LoadGameEvent, SceneRestore, tick_playtime,
+    handle_save_events, handle_new_game_events, handle_load_events, handle_auto_save, handle_quick_save_input,
+    restore_loaded_scene,
+};
+use crate::core::save::read_log::{ReadLog, track_read_lines, save_read_log};
+use crate::core::story::manifest::{StoryManifest, StoryManifestLoader};
 use crate::core::language::{
     manager::{load_language_packs, check_language_loading},
     types::{LanguagePack, LanguageLoader},
@@ -19,38 +40,100 @@ use crate::core::text::{
     styles::{TextStyleResource, ensure_text_styles_initialized},
     builder::update_localized_text,
 };
-use crate::types::{DialogScene, DialogLoader};
+use crate::types::{DialogScene, DialogLoader, InkLoader, ScriptLoader, YarnLoader};
 use crate::ui::{
-    dialog::{setup_dialog_ui, handle_text_interaction, update_dialog_fonts, paused_typewriter_system},
+    dialog::{
+        setup_dialog_ui, handle_text_interaction, update_dialog_fonts, paused_typewriter_system, advance_control_entries,
+        update_dialog_indicators, toggle_dialog_ui, DialogUiHidden,
+    },
     choice::{manage_choice_display, highlight_choice_button},
     main_menu::{setup_main_menu, handle_menu_button_hover, handle_menu_buttons, cleanup_main_menu, setup_loading_screen, handle_loading_transition},
-    settings::{setup_settings_ui, handle_settings_button_hover, handle_settings_buttons, update_settings_values, cleanup_settings, ResolutionDropdownState},
+    settings::{
+        setup_settings_ui, handle_settings_button_hover, handle_settings_buttons, update_settings_values, cleanup_settings,
+        handle_read_settings_buttons, update_read_settings_values, handle_auto_speed_slider, update_auto_speed_slider,
+        handle_auto_speed_focus, ResolutionDropdownState,
+    },
     pause::{setup_pause_ui, handle_pause_button_hover, handle_pause_buttons, cleanup_pause_ui},
+    save_load::{
+        SaveLoadMode, setup_save_load_ui, handle_save_load_button_hover, handle_save_load_buttons,
+        refresh_save_slots, cleanup_save_load_ui,
+    },
+    backlog::{
+        setup_backlog_ui, handle_backlog_input, scroll_backlog, handle_backlog_line_hover,
+        handle_backlog_line_click, cleanup_backlog_ui,
+    },
+    focus::{FocusState, navigate_focus, update_focus_highlight},
+    key_bindings::{
+        RebindState, setup_key_bindings_ui, handle_key_bindings_button_hover, handle_key_bindings_buttons,
+        handle_rebind_input, update_binding_texts, cleanup_key_bindings_ui,
+    },
 };
 
-pub struct VNPlugin;
+#[derive(Default)]
+pub struct VNPlugin {
+    actions: ActionRegistry,
+}
+
+impl VNPlugin {
+    /// ลงทะเบียน custom action ที่ใช้ใน `DialogEntry.actions` ได้ เช่น `"unlock_cg:cg_01"`
+    pub fn with_action<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(&mut World, &[String]) + Send + Sync + 'static,
+    {
+        let handler: ActionHandler = std::sync::Arc::new(handler);
+        self.actions.register(name, handler);
+        self
+    }
+}
 
 impl Plugin for VNPlugin {
     fn build(&self, app: &mut App) {
         app
+            // Action registry ต้องมาก่อน DialogLoader เพื่อให้ตรวจ custom action ได้ตอนโหลด
+            .insert_resource(self.actions.clone())
+
             // Core systems
             .init_state::<GameState>()
             .init_asset::<DialogScene>()
             .init_asset::<LanguagePack>()
+            .init_asset::<StoryManifest>()
             .init_asset_loader::<DialogLoader>()
+            .init_asset_loader::<ScriptLoader>()
+            .init_asset_loader::<InkLoader>()
+            .init_asset_loader::<YarnLoader>()
             .init_asset_loader::<LanguageLoader>()
+            .init_asset_loader::<StoryManifestLoader>()
 
             // Resources
             .init_resource::<VNState>()
+            .init_resource::<StoryVariables>()
             .init_resource::<DialogResource>()
             .init_resource::<DialogHistory>()
             .init_resource::<ChoiceState>()
             .init_resource::<DialogManager>()
             .init_resource::<PreviousState>()
             .init_resource::<LanguageResource>()
+            // main.rs insert settings ที่อ่านจากไฟล์ไว้ก่อน (init_resource ไม่เขียนทับ)
             .init_resource::<SettingsResource>()
             .init_resource::<ResolutionDropdownState>()
             .init_resource::<TextStyleResource>()
+            .init_resource::<TransitionState>()
+            .init_resource::<ActionWait>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<PendingCustomActions>()
+            .init_resource::<PlayTime>()
+            .init_resource::<AutoSaveRotation>()
+            .init_resource::<SceneRestore>()
+            .init_resource::<SaveLoadMode>()
+            .init_resource::<Backlog>()
+            .init_resource::<SkipState>()
+            .init_resource::<AutoState>()
+            .init_resource::<ActionState>()
+            .init_resource::<DialogUiHidden>()
+            .init_resource::<RebindState>()
+            .init_resource::<FocusState>()
+            // อ่านแล้ว/ยังไม่อ่าน ใช้ร่วมกันทุก save
+            .insert_resource(ReadLog::load())
 
             // Events
             .add_event::<StageChangeEvent>()
@@ -58,25 +141,49 @@ impl Plugin for VNPlugin {
             .add_event::<ChangeStateEvent>()
             .add_event::<LanguageChangeEvent>()
             .add_event::<SettingsChangeEvent>()
+            .add_event::<DialogActionEvent>()
+            .add_event::<NewGameEvent>()
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>()
+            .add_event::<RollbackEvent>()
 
             // Startup systems
             .add_systems(Startup, (
                 setup_global_camera,
+                apply_loaded_settings,
                 load_language_packs,
+                load_story_manifest,
             ))
 
             // Global systems
             .add_systems(Update, (
                 handle_state_changes,
                 handle_pause_input,
+                handle_backlog_input,
                 check_language_loading,
+                register_story_scenes,
+                preload_scene_assets.after(register_story_scenes),
+                check_scene_links.after(register_story_scenes),
                 sync_language_with_vn_state,
                 sync_vn_state_with_language,
                 update_localized_text,
                 ensure_text_styles_initialized,
                 // เพิ่ม conditional cleanup
                 conditional_cleanup_game_scene,
+                // New game และ Save/Load (ทั้งจากหน้า save/load และ quick save)
+                handle_new_game_events,
+                handle_save_events,
+                handle_load_events,
             ))
+            .add_systems(Last, save_read_log)
+            // แปลงปุ่มเป็น action ก่อนทุกระบบใน Update
+            .add_systems(PreUpdate, update_action_state.after(bevy::input::InputSystem))
+            // focus ของปุ่ม (คีย์บอร์ด/gamepad) ต้องกดปุ่มก่อนระบบปุ่มใน Update อ่าน `Interaction`
+            .add_systems(PreUpdate, navigate_focus
+                .after(update_action_state)
+                .after(bevy::ui::UiSystem::Focus))
+            // สี focus ทับสีที่ระบบ hover ตั้งไว้ใน Update
+            .add_systems(PostUpdate, update_focus_highlight)
 
             // Main Menu
             .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
@@ -92,9 +199,24 @@ impl Plugin for VNPlugin {
                 handle_settings_button_hover,
                 handle_settings_buttons,
                 update_settings_values,
+                handle_read_settings_buttons,
+                update_read_settings_values.after(handle_read_settings_buttons),
+                handle_auto_speed_slider,
+                handle_auto_speed_focus,
+                update_auto_speed_slider.after(handle_auto_speed_slider).after(handle_auto_speed_focus),
             ).run_if(in_state(GameState::Settings)))
             .add_systems(OnExit(GameState::Settings), cleanup_settings)
 
+            // Key Bindings
+            .add_systems(OnEnter(GameState::KeyBindings), setup_key_bindings_ui)
+            .add_systems(Update, (
+                handle_key_bindings_button_hover,
+                handle_key_bindings_buttons,
+                handle_rebind_input.before(handle_key_bindings_buttons),
+                update_binding_texts.after(handle_key_bindings_buttons),
+            ).run_if(in_state(GameState::KeyBindings)))
+            .add_systems(OnExit(GameState::KeyBindings), cleanup_key_bindings_ui)
+
             // Pause - render ทับ dialog โดยไม่ cleanup
             .add_systems(OnEnter(GameState::Paused), setup_pause_ui)
             .add_systems(Update, (
@@ -103,6 +225,24 @@ impl Plugin for VNPlugin {
             ).run_if(in_state(GameState::Paused)))
             .add_systems(OnExit(GameState::Paused), cleanup_pause_ui)
 
+            // Save/Load - render ทับเกมเหมือน Pause
+            .add_systems(OnEnter(GameState::SaveLoad), setup_save_load_ui)
+            .add_systems(Update, (
+                handle_save_load_button_hover,
+                handle_save_load_buttons.before(handle_save_events).before(handle_load_events),
+                refresh_save_slots.after(handle_save_events),
+            ).run_if(in_state(GameState::SaveLoad)))
+            .add_systems(OnExit(GameState::SaveLoad), cleanup_save_load_ui)
+
+            // Backlog - render ทับเกมเหมือน Pause
+            .add_systems(OnEnter(GameState::Backlog), setup_backlog_ui)
+            .add_systems(Update, (
+                scroll_backlog,
+                handle_backlog_line_hover,
+                handle_backlog_line_click,
+            ).run_if(in_state(GameState::Backlog)))
+            .add_systems(OnExit(GameState::Backlog), cleanup_backlog_ui)
+
             // Loading
             .add_systems(OnEnter(GameState::Loading), setup_loading_screen)
             .add_systems(Update, handle_loading_transition.run_if(in_state(GameState::Loading)))
@@ -111,11 +251,14 @@ impl Plugin for VNPlugin {
             // In-Game - ลบ OnExit cleanup ออก
             .add_systems(OnEnter(GameState::InGame), (
                 setup_dialog_ui_if_needed,
-                load_dialogs_if_needed,
                 setup_scene_background_if_needed,
             ))
             .add_systems(Update, (
                 // Core dialog management
+                reload_modified_scenes.before(manage_dialog_state),
+                advance_control_entries
+                    .after(reload_modified_scenes)
+                    .before(manage_dialog_state),
                 manage_dialog_state
                     .before(handle_text_interaction)
                     .before(manage_choice_display),
@@ -136,6 +279,49 @@ impl Plugin for VNPlugin {
                 update_characters.after(setup_characters),
                 update_background.after(manage_dialog_state),
                 check_character_assets,
+
+                // Transitions (ต้องจับภาพพื้นหลังเดิมก่อน update_background เปลี่ยน)
+                start_entry_transition.after(manage_dialog_state).before(update_background),
+                update_transitions.after(start_entry_transition),
+
+                // Dialog actions
+                handle_dialog_actions.after(handle_text_interaction),
+                run_custom_actions.after(handle_dialog_actions),
+                tick_action_wait,
+                apply_screen_shake,
+                tick_playtime,
+            ).run_if(in_state(GameState::InGame)))
+            .add_systems(Update, (
+                // Save/Load - auto-save หลังบรรทัดใหม่และตัวเลือกแสดงแล้ว
+                handle_auto_save.after(manage_choice_display),
+                handle_quick_save_input.before(handle_save_events).before(handle_load_events),
+                restore_loaded_scene.after(update_background).after(update_characters),
+
+                // Rollback
+                handle_rollback
+                    .after(advance_control_entries)
+                    .before(manage_dialog_state)
+                    .before(handle_text_interaction),
+                record_history_step.after(update_background),
+
+                // Skip mode และบันทึกบรรทัดที่อ่านแล้ว
+                handle_skip_input,
+                update_skip
+                    .after(handle_skip_input)
+                    .after(manage_dialog_state)
+                    .before(handle_text_interaction),
+                track_read_lines.after(manage_dialog_state),
+                update_dialog_indicators.after(update_skip).after(track_read_lines),
+
+                // Auto mode
+                handle_auto_input,
+                update_auto
+                    .after(handle_auto_input)
+                    .after(update_skip)
+                    .before(handle_text_interaction),
+
+                // ซ่อน/แสดงกล่องข้อความ
+                toggle_dialog_ui.after(handle_text_interaction),
             ).run_if(in_state(GameState::InGame)));
         // ไม่มี OnExit(GameState::InGame) cleanup
     }
@@ -173,6 +359,8 @@ fn conditional_cleanup_game_scene(
             With<crate::core::scene::character::CharacterSprite>,
             With<crate::ui::choice::ChoiceContainer>,
             With<crate::ui::choice::ChoiceOverlay>,
+            With<crate::core::scene::transition::TransitionOverlay>,
+            With<crate::core::scene::transition::TransitionLayer>,
         )>
     )>,
     mut cleanup_happened: Local<bool>,
@@ -182,6 +370,8 @@ fn conditional_cleanup_game_scene(
             (GameState::InGame, GameState::MainMenu) => true,
             (GameState::InGame, GameState::Loading) => true,
             (GameState::Paused, GameState::MainMenu) => true,
+            // โหลด save ระหว่างเล่น: ล้าง scene เดิมแล้วสร้างใหม่ผ่านหน้า Loading
+            (GameState::SaveLoad, GameState::Loading) => true,
             _ => false,
         };
 
@@ -220,16 +410,6 @@ fn setup_dialog_ui_if_needed(
     }
 }
 
-fn load_dialogs_if_needed(
-    asset_server: Res<AssetServer>,
-    dialog_resource: ResMut<DialogResource>,
-    vn_state: ResMut<VNState>,
-) {
-    if dialog_resource.scenes.is_empty() {
-        load_dialogs(asset_server, dialog_resource, vn_state);
-    }
-}
-
 fn setup_scene_background_if_needed(
     commands: Commands,
     asset_server: Res<AssetServer>,
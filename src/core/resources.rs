@@ -1,39 +1,105 @@
+use anyhow::{bail, Context};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::types::DialogScene;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::types::{DialogScene, JumpTarget};
+use crate::core::input::action::InputMap;
 use crate::core::language::types::LanguageCode;
+use crate::core::story::variables::StoryVariables;
+use crate::util::paths::config_dir;
 
-#[derive(Resource, Default)]
+/// version ของไฟล์ settings (เพิ่มเมื่อเปลี่ยนความหมายของ field เดิม)
+const SETTINGS_VERSION: u32 = 1;
+
+/// จำนวนบรรทัดย้อนหลังสูงสุดที่ rollback ได้
+const MAX_ROLLBACK_STEPS: usize = 100;
+
+/// สถานะทั้งหมดตอนที่บรรทัดหนึ่งเริ่มแสดง (ก่อน action ของบรรทัดนั้นทำงาน) ใช้ย้อนกลับแม้ข้าม scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryStep {
+    pub scene: String,
+    pub stage: usize,
+    #[serde(default)]
+    pub call_stack: Vec<SceneReturn>,
+    #[serde(default)]
+    pub variables: StoryVariables,
+    /// จำนวนตัวเลือกที่เลือกไปแล้วตอนนั้น (`ChoiceState.history`)
+    #[serde(default)]
+    pub choice_count: usize,
+    /// ภาพพื้นหลังที่แสดงอยู่
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
 pub struct DialogHistory {
+    /// ตัวเลือกที่เลือก (from_stage, choice_index, to_stage)
+    #[serde(default)]
     history: Vec<(usize, usize, usize)>,
-    previous_stages: Vec<usize>,
+    /// บรรทัดที่แสดงไปแล้ว บนสุดคือบรรทัดปัจจุบัน
+    #[serde(default)]
+    steps: Vec<HistoryStep>,
 }
 impl DialogHistory {
     pub fn add_choice(&mut self, from_stage: usize, choice_index: usize, to_stage: usize) {
         self.history.push((from_stage, choice_index, to_stage));
-        self.previous_stages.push(from_stage);
     }
 
-    pub fn add_stage(&mut self, stage: usize) {
-        self.previous_stages.push(stage);
+    /// บันทึกบรรทัดที่เพิ่งแสดง (บรรทัดเดิมที่แสดงซ้ำ เช่นตอนเปลี่ยนภาษา จะไม่ถูกบันทึกซ้ำ)
+    pub fn add_step(&mut self, step: HistoryStep) {
+        if self.is_current(&step.scene, step.stage) {
+            return;
+        }
+        self.steps.push(step);
+        if self.steps.len() > MAX_ROLLBACK_STEPS {
+            self.steps.remove(0);
+        }
+    }
+
+    /// บรรทัดบนสุดคือตำแหน่งนี้หรือไม่
+    pub fn is_current(&self, scene: &str, stage: usize) -> bool {
+        self.steps
+            .last()
+            .is_some_and(|step| step.scene == scene && step.stage == stage)
     }
 
-    pub fn go_back(&mut self) -> Option<usize> {
-        if self.previous_stages.is_empty() {
-            return None;
-        }
+    /// ทิ้งบรรทัดปัจจุบันแล้วคืนสถานะของบรรทัดก่อนหน้า ตัวเลือกที่เลือกหลังจากนั้นถูกยกเลิก
+    ///
+    /// บรรทัดที่คืนมาจะถูกบันทึกใหม่เมื่อแสดงอีกครั้ง
+    pub fn go_back(&mut self) -> Option<HistoryStep> {
+        let index = self.steps.len().checked_sub(2)?;
+        Some(self.rewind(index))
+    }
 
-        if !self.history.is_empty() &&
-            self.history.last().unwrap().2 == *self.previous_stages.last().unwrap() {
-            self.history.pop();
-        }
+    /// เหมือน `go_back` แต่ย้อนไปถึงครั้งล่าสุดที่แสดงบรรทัดนี้ (`None` ถ้าไม่อยู่ในประวัติแล้ว)
+    pub fn go_back_to(&mut self, scene: &str, stage: usize) -> Option<HistoryStep> {
+        let current = self.steps.len().checked_sub(1)?;
+        let index = self.steps[..current]
+            .iter()
+            .rposition(|step| step.scene == scene && step.stage == stage)?;
+        Some(self.rewind(index))
+    }
 
-        self.previous_stages.pop()
+    /// บรรทัดนี้ยังย้อนกลับไปได้หรือไม่ (ไม่นับบรรทัดปัจจุบัน)
+    pub fn can_go_back_to(&self, scene: &str, stage: usize) -> bool {
+        let current = self.steps.len().saturating_sub(1);
+        self.steps[..current]
+            .iter()
+            .any(|step| step.scene == scene && step.stage == stage)
+    }
+
+    fn rewind(&mut self, index: usize) -> HistoryStep {
+        let step = self.steps.swap_remove(index);
+        self.steps.truncate(index);
+        self.history.truncate(step.choice_count);
+        step
     }
 
     pub fn clear(&mut self) {
         self.history.clear();
-        self.previous_stages.clear();
+        self.steps.clear();
     }
 }
 
@@ -46,6 +112,13 @@ pub struct StageChangeEvent {
 #[derive(Event)]
 pub struct DialogResetEvent;
 
+/// ตำแหน่งที่ `return` จะกลับไป: scene ของผู้เรียกและ entry ถัดจาก entry ที่เรียก
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SceneReturn {
+    pub scene: String,
+    pub stage: usize,
+}
+
 /// VNState ที่ใช้ language system ใหม่
 #[derive(Resource)]
 pub struct VNState {
@@ -53,8 +126,12 @@ pub struct VNState {
     pub language: String, // ยังคงใช้ string เพื่อ backward compatibility
     pub current_scene: String,
     pub current_scene_handle: Option<Handle<DialogScene>>,
+    /// stack ของ `call_scene` (บนสุดคือผู้เรียกล่าสุด) ต้องเก็บคู่กับ stage เสมอ
+    pub call_stack: Vec<SceneReturn>,
     pub stage_changed: bool,
     pub dialog_needs_reset: bool,
+    /// ขอ auto-save เมื่อบรรทัดปัจจุบันแสดงแล้ว (ตั้งตอนเปลี่ยน scene และก่อนแสดงตัวเลือก)
+    pub autosave_pending: bool,
 }
 
 impl Default for VNState {
@@ -62,10 +139,12 @@ impl Default for VNState {
         Self {
             stage: 0,
             language: "thai".to_string(), // default ไทย
-            current_scene: "intro".to_string(),
+            current_scene: String::new(), // ตั้งจาก `start` ใน story.json
             current_scene_handle: None,
+            call_stack: Vec::new(),
             stage_changed: false,
             dialog_needs_reset: true,
+            autosave_pending: false,
         }
     }
 }
@@ -79,6 +158,13 @@ impl VNState {
         }
     }
 
+    /// เหมือน change_stage แต่รีเซ็ต dialog เสมอ แม้ stage จะเท่าเดิม (ใช้ตอนเปลี่ยน scene)
+    pub fn force_stage(&mut self, new_stage: usize) {
+        self.stage = new_stage;
+        self.stage_changed = true;
+        self.dialog_needs_reset = true;
+    }
+
     /// เปลี่ยนภาษาและแจ้ง dialog ว่าต้องรีเซ็ต
     pub fn change_language(&mut self, new_language: String) {
         if self.language != new_language {
@@ -89,12 +175,7 @@ impl VNState {
 
     /// สำหรับ sync กับ language system
     pub fn sync_with_language_system(&mut self, language_code: &LanguageCode) {
-        let lang_str = match language_code {
-            LanguageCode::Thai => "thai",
-            LanguageCode::English => "english",
-            LanguageCode::Japanese => "japanese",
-        };
-        self.change_language(lang_str.to_string());
+        self.change_language(language_code.dialog_key().to_string());
     }
 
     pub fn mark_dialog_reset(&mut self) {
@@ -119,12 +200,115 @@ impl DialogResource {
             self.current_scene = Some(handle.clone());
             vn_state.current_scene = scene_name.to_string();
             vn_state.current_scene_handle = Some(handle.clone());
-            vn_state.change_stage(0);
+            vn_state.force_stage(0);
+            vn_state.autosave_pending = true;
             true
         } else {
             false
         }
     }
+
+    /// เข้า sub-scene โดยจำตำแหน่งถัดจาก entry ปัจจุบันไว้ใน `call_stack`
+    pub fn call_scene(
+        &mut self,
+        scene: &str,
+        label: Option<&str>,
+        vn_state: &mut VNState,
+        dialog_scenes: &Assets<DialogScene>,
+    ) -> bool {
+        let caller = SceneReturn {
+            scene: vn_state.current_scene.clone(),
+            stage: vn_state.stage + 1,
+        };
+        let target = JumpTarget::Scene {
+            scene: scene.to_string(),
+            label: label.map(str::to_string),
+        };
+        if !self.jump(&target, vn_state, dialog_scenes) {
+            return false;
+        }
+        vn_state.call_stack.push(caller);
+        true
+    }
+
+    /// กลับไปยังผู้เรียกล่าสุดใน `call_stack`; คืน false ถ้า stack ว่าง
+    pub fn return_from_scene(&mut self, vn_state: &mut VNState, dialog_scenes: &Assets<DialogScene>) -> bool {
+        let Some(caller) = vn_state.call_stack.pop() else {
+            warn!("`return` in scene `{}` without a matching `call_scene`", vn_state.current_scene);
+            return false;
+        };
+        if !self.change_scene(&caller.scene, vn_state) {
+            error!("Cannot return to unknown scene `{}`", caller.scene);
+            return false;
+        }
+
+        // ผู้เรียกเป็น entry สุดท้ายของ scene: วนกลับต้น scene เหมือนการเดินต่อปกติ
+        let entry_count = self
+            .current_scene
+            .as_ref()
+            .and_then(|handle| dialog_scenes.get(handle))
+            .map_or(0, |scene| scene.entries.len());
+        vn_state.force_stage(if caller.stage < entry_count { caller.stage } else { 0 });
+        true
+    }
+
+    /// กระโดดไปยัง target (label ภายใน scene ถูก resolve ไว้แล้วตอนโหลด)
+    pub fn jump(
+        &mut self,
+        target: &JumpTarget,
+        vn_state: &mut VNState,
+        dialog_scenes: &Assets<DialogScene>,
+    ) -> bool {
+        match target {
+            JumpTarget::Stage(stage) => {
+                vn_state.change_stage(*stage);
+                true
+            }
+            JumpTarget::Label(label) => {
+                let stage = self
+                    .current_scene
+                    .as_ref()
+                    .and_then(|handle| dialog_scenes.get(handle))
+                    .and_then(|scene| scene.label_index(label));
+                match stage {
+                    Some(stage) => {
+                        vn_state.change_stage(stage);
+                        true
+                    }
+                    None => {
+                        error!("Unknown label `{}` in scene `{}`", label, vn_state.current_scene);
+                        false
+                    }
+                }
+            }
+            JumpTarget::Scene { scene, label } => {
+                let stage = match label {
+                    Some(label) => {
+                        let index = self
+                            .scenes
+                            .get(scene)
+                            .and_then(|handle| dialog_scenes.get(handle))
+                            .and_then(|dialog_scene| dialog_scene.label_index(label));
+                        match index {
+                            Some(index) => index,
+                            None => {
+                                error!("Unknown jump target `{}`", target);
+                                return false;
+                            }
+                        }
+                    }
+                    None => 0,
+                };
+
+                if !self.change_scene(scene, vn_state) {
+                    error!("Unknown scene `{}` in jump target `{}`", scene, target);
+                    return false;
+                }
+                vn_state.force_stage(stage);
+                true
+            }
+        }
+    }
 }
 
 #[derive(Resource, Default)]
@@ -156,6 +340,14 @@ pub struct SettingsResource {
     pub language: LanguageCode,
     pub resolution: (f32, f32),
     pub fullscreen: bool,
+    /// โหมดข้ามข้อความข้ามบรรทัดที่ยังไม่เคยอ่านด้วย
+    pub skip_unread: bool,
+    /// แสดงป้ายบนบรรทัดที่ยังไม่เคยอ่าน
+    pub mark_unread: bool,
+    /// ตัวคูณความเร็วของโหมด auto (มากขึ้น = รอสั้นลง)
+    pub auto_speed: f32,
+    /// ปุ่มของแต่ละ action (ตั้งได้ในหน้าตั้งค่าปุ่ม)
+    pub bindings: InputMap,
     pub changed: bool,
 }
 #[derive(Event)]
@@ -166,6 +358,10 @@ impl Default for SettingsResource {
             language: LanguageCode::Thai,
             resolution: (1280.0, 720.0),
             fullscreen: false,
+            skip_unread: false,
+            mark_unread: true,
+            auto_speed: 1.0,
+            bindings: InputMap::default(),
             changed: false,
         }
     }
@@ -184,12 +380,93 @@ impl SettingsResource {
         self.changed
     }
 
-    pub fn load_from_file(&mut self, _path: &str) {
-        // TODO: Implement loading from file
+    /// ไฟล์ settings ในโฟลเดอร์ config ของผู้ใช้
+    pub fn path() -> anyhow::Result<PathBuf> {
+        let dir = config_dir().context("Cannot find a user config directory for settings")?;
+        Ok(dir.join("settings.json"))
     }
 
-    pub fn save_to_file(&self, _path: &str) {
-        info!("Settings saved: {}x{}, Fullscreen: {}, Language: {:?}",
-              self.resolution.0, self.resolution.1, self.fullscreen, self.language);
+    /// อ่าน settings ก่อนสร้างหน้าต่าง ถ้ายังไม่มีไฟล์ใช้ค่าเริ่มต้น
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        let mut settings = Self::default();
+        if path.exists() {
+            settings.load_from_file(&path)?;
+        }
+        Ok(settings)
     }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.save_to_file(&Self::path()?)
+    }
+
+    /// field ที่ไม่มีในไฟล์ (เช่นไฟล์จาก version เก่า) คงค่าเดิมไว้
+    pub fn load_from_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        let bytes = fs::read(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let file: SettingsFile = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Invalid settings file {}", path.display()))?;
+        if file.version > SETTINGS_VERSION {
+            bail!(
+                "Settings file {} has version {} (this build supports up to {})",
+                path.display(),
+                file.version,
+                SETTINGS_VERSION
+            );
+        }
+
+        if let Some(language) = file.language.as_deref().and_then(LanguageCode::from_str) {
+            self.language = language;
+        }
+        if let Some((width, height)) = file.resolution.filter(|(width, height)| *width > 0.0 && *height > 0.0) {
+            self.resolution = (width, height);
+        }
+        self.fullscreen = file.fullscreen.unwrap_or(self.fullscreen);
+        self.skip_unread = file.skip_unread.unwrap_or(self.skip_unread);
+        self.mark_unread = file.mark_unread.unwrap_or(self.mark_unread);
+        self.auto_speed = file.auto_speed.unwrap_or(self.auto_speed);
+        if let Some(mut bindings) = file.bindings {
+            bindings.fill_missing();
+            self.bindings = bindings;
+        }
+        Ok(())
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            language: Some(self.language.as_str().to_string()),
+            resolution: Some(self.resolution),
+            fullscreen: Some(self.fullscreen),
+            skip_unread: Some(self.skip_unread),
+            mark_unread: Some(self.mark_unread),
+            auto_speed: Some(self.auto_speed),
+            bindings: Some(self.bindings.clone()),
+        };
+        let json = serde_json::to_vec_pretty(&file)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json).with_context(|| format!("Cannot write {}", temp.display()))?;
+        fs::rename(&temp, path).with_context(|| format!("Cannot write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// รูปแบบไฟล์ settings ทุก field เป็น optional เพื่อให้อ่านไฟล์เก่าและเพิ่ม field ใหม่ได้
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    language: Option<String>,
+    resolution: Option<(f32, f32)>,
+    fullscreen: Option<bool>,
+    skip_unread: Option<bool>,
+    mark_unread: Option<bool>,
+    auto_speed: Option<f32>,
+    bindings: Option<InputMap>,
+}
+
+/// ใช้ภาษาจาก settings ที่โหลดมาเป็นภาษาเริ่มต้น (ก่อน Language System เริ่มทำงาน)
+pub fn apply_loaded_settings(settings: Res<SettingsResource>, mut vn_state: ResMut<VNState>) {
+    vn_state.sync_with_language_system(&settings.language);
 }
\ No newline at end of file
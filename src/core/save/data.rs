@@ -0,0 +1,169 @@
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::core::resources::{DialogHistory, SceneReturn};
+use crate::core::story::variables::StoryVariables;
+use crate::util::paths::save_dir;
+
+/// version ของรูปแบบไฟล์ save (เพิ่มเมื่อเปลี่ยนโครงสร้าง `SaveData`)
+pub const SAVE_VERSION: u32 = 2;
+
+/// จำนวน slot ในหน้า save/load
+pub const SAVE_SLOTS: usize = 6;
+
+/// จำนวน auto-save ที่เก็บไว้ (เขียนทับอันเก่าสุดวนไป)
+pub const AUTO_SAVE_SLOTS: usize = 3;
+
+/// ช่องเก็บ save แต่ละแบบ (เลขเริ่มที่ 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveSlot {
+    Manual(usize),
+    Quick,
+    Auto(usize),
+}
+
+impl SaveSlot {
+    fn file_name(&self) -> String {
+        match self {
+            SaveSlot::Manual(slot) => format!("slot_{:02}.json", slot),
+            SaveSlot::Quick => "quick.json".to_string(),
+            SaveSlot::Auto(slot) => format!("auto_{:02}.json", slot),
+        }
+    }
+}
+
+impl fmt::Display for SaveSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveSlot::Manual(slot) => write!(f, "slot {}", slot),
+            SaveSlot::Quick => write!(f, "quick save"),
+            SaveSlot::Auto(slot) => write!(f, "auto-save {}", slot),
+        }
+    }
+}
+
+/// ข้อมูลที่แสดงในหน้า save/load โดยไม่ต้องโหลดเกม
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveMeta {
+    /// เวลาที่ save (unix seconds, UTC)
+    pub timestamp: u64,
+    /// ชื่อ scene ตอน save
+    pub scene: String,
+    /// ข้อความของบรรทัดที่กำลังแสดง (ตัด markup และ tag ออกแล้ว)
+    pub text: String,
+    /// เวลาเล่นสะสม (วินาที)
+    pub playtime: f64,
+}
+
+/// สถานะของเนื้อเรื่องทั้งหมดที่เก็บลงไฟล์ save
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub meta: SaveMeta,
+    pub scene: String,
+    pub stage: usize,
+    pub language: String,
+    #[serde(default)]
+    pub call_stack: Vec<SceneReturn>,
+    #[serde(default)]
+    pub history: DialogHistory,
+    /// `ChoiceState.history`
+    #[serde(default)]
+    pub choices: Vec<usize>,
+    #[serde(default)]
+    pub variables: StoryVariables,
+    /// ภาพพื้นหลังที่แสดงอยู่ตอน save (entry อาจไม่ได้กำหนดพื้นหลังเอง)
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+/// path ของไฟล์ save ของ slot
+pub fn slot_path(slot: SaveSlot) -> anyhow::Result<PathBuf> {
+    let dir = save_dir().context("Cannot find a user data directory for save files")?;
+    Ok(dir.join(slot.file_name()))
+}
+
+/// เขียน save ลง slot (เขียนไฟล์ชั่วคราวก่อนแล้วค่อย rename กันไฟล์เสียถ้าเกมปิดกลางคัน)
+pub fn write_slot(slot: SaveSlot, data: &SaveData) -> anyhow::Result<()> {
+    let path = slot_path(slot)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    }
+
+    let json = serde_json::to_vec_pretty(data)?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, json).with_context(|| format!("Cannot write {}", temp.display()))?;
+    fs::rename(&temp, &path).with_context(|| format!("Cannot write {}", path.display()))?;
+    Ok(())
+}
+
+/// อ่าน save จาก slot ไฟล์จาก version ที่ใหม่กว่าเกมถือว่าอ่านไม่ได้
+pub fn read_slot(slot: SaveSlot) -> anyhow::Result<SaveData> {
+    let path = slot_path(slot)?;
+    let bytes = fs::read(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let data: SaveData = serde_json::from_slice(&bytes)
+        .with_context(|| format!("Invalid save file {}", path.display()))?;
+    if data.version > SAVE_VERSION {
+        bail!(
+            "Save file {} has version {} (this build supports up to {})",
+            path.display(),
+            data.version,
+            SAVE_VERSION
+        );
+    }
+    Ok(data)
+}
+
+/// metadata ของ slot (`None` = slot ว่างหรืออ่านไม่ได้)
+pub fn read_meta(slot: SaveSlot) -> Option<SaveMeta> {
+    let path = slot_path(slot).ok()?;
+    if !path.exists() {
+        return None;
+    }
+    read_slot(slot)
+        .map_err(|err| bevy::log::warn!("{:#}", err))
+        .ok()
+        .map(|data| data.meta)
+}
+
+/// เวลาปัจจุบันเป็น unix seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// `2025-01-31 18:05` (UTC)
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // แปลงจำนวนวันนับจาก 1970-01-01 เป็นวันที่ (Howard Hinnant, `civil_from_days`)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60
+    )
+}
+
+/// `1:05:09`
+pub fn format_playtime(playtime: f64) -> String {
+    let total = playtime.max(0.0) as u64;
+    format!("{}:{:02}:{:02}", total / 3_600, total % 3_600 / 60, total % 60)
+}
@@ -0,0 +1,317 @@
+use bevy::prelude::*;
+use crate::core::dialog::backlog::Backlog;
+use crate::core::dialog::choice::ChoiceState;
+use crate::core::dialog::typewriter::plain_text;
+use crate::core::game_state::{ChangeStateEvent, GameState};
+use crate::core::input::action::{ActionState, InputAction};
+use crate::core::resources::{DialogHistory, DialogManager, DialogResource, VNState};
+use crate::core::save::data::{
+    now, read_meta, read_slot, write_slot, SaveData, SaveMeta, SaveSlot, AUTO_SAVE_SLOTS, SAVE_VERSION,
+};
+use crate::core::scene::background::Background;
+use crate::core::scene::character::{place_characters, CharacterSprite};
+use crate::core::scene::transition::TransitionState;
+use crate::core::story::manifest::{StoryManifest, StoryResource};
+use crate::core::story::variables::StoryVariables;
+use crate::types::DialogScene;
+use crate::util::identifier::texture;
+
+/// เวลาเล่นสะสม (นับเฉพาะตอนอยู่ใน InGame)
+#[derive(Resource, Default)]
+pub struct PlayTime {
+    pub seconds: f64,
+}
+
+/// บันทึกเกมลง slot
+#[derive(Event)]
+pub struct SaveGameEvent {
+    pub slot: SaveSlot,
+}
+
+/// เริ่มเกมใหม่จาก `start` ใน story.json (ล้างสถานะของรอบก่อนทั้งหมด)
+#[derive(Event)]
+pub struct NewGameEvent;
+
+/// โหลดเกมจาก slot
+#[derive(Event)]
+pub struct LoadGameEvent {
+    pub slot: SaveSlot,
+}
+
+/// auto-save ช่องถัดไปที่จะเขียน (หาจากไฟล์ที่ใหม่สุดเมื่อใช้ครั้งแรก)
+#[derive(Resource, Default)]
+pub struct AutoSaveRotation {
+    next: Option<usize>,
+}
+
+impl AutoSaveRotation {
+    fn next_slot(&mut self) -> SaveSlot {
+        let slot = *self.next.get_or_insert_with(|| {
+            (1..=AUTO_SAVE_SLOTS)
+                .filter_map(|slot| read_meta(SaveSlot::Auto(slot)).map(|meta| (meta.timestamp, slot)))
+                .max()
+                .map_or(1, |(_, newest)| newest % AUTO_SAVE_SLOTS + 1)
+        });
+        self.next = Some(slot % AUTO_SAVE_SLOTS + 1);
+        SaveSlot::Auto(slot)
+    }
+}
+
+/// ฉากที่ต้องจัดให้ตรงกับตอน save หลังโหลดหรือ rollback (พื้นหลังและตำแหน่งตัวละคร)
+#[derive(Resource, Default)]
+pub struct SceneRestore {
+    pending: bool,
+    background: Option<String>,
+}
+
+impl SceneRestore {
+    /// จัดฉากใหม่เมื่อ entry ปัจจุบันพร้อม (`None` = ใช้พื้นหลังตาม entry)
+    pub fn request(&mut self, background: Option<String>) {
+        self.pending = true;
+        self.background = background;
+    }
+}
+
+pub fn tick_playtime(time: Res<Time>, mut playtime: ResMut<PlayTime>) {
+    playtime.seconds += time.delta_seconds_f64();
+}
+
+/// เก็บสถานะเนื้อเรื่องปัจจุบันเป็น `SaveData`
+pub fn capture_save(
+    state: &VNState,
+    history: &DialogHistory,
+    choice_state: &ChoiceState,
+    variables: &StoryVariables,
+    dialog_manager: &DialogManager,
+    playtime: &PlayTime,
+    background: Option<&Background>,
+) -> SaveData {
+    SaveData {
+        version: SAVE_VERSION,
+        meta: SaveMeta {
+            timestamp: now(),
+            scene: state.current_scene.clone(),
+            text: plain_text(&dialog_manager.current_dialog_text),
+            playtime: playtime.seconds,
+        },
+        scene: state.current_scene.clone(),
+        stage: state.stage,
+        language: state.language.clone(),
+        call_stack: state.call_stack.clone(),
+        history: history.clone(),
+        choices: choice_state.history.clone(),
+        variables: variables.clone(),
+        background: background.map(|background| background.current_path.clone()),
+    }
+}
+
+pub fn handle_save_events(
+    mut events: EventReader<SaveGameEvent>,
+    state: Res<VNState>,
+    history: Res<DialogHistory>,
+    choice_state: Res<ChoiceState>,
+    variables: Res<StoryVariables>,
+    dialog_manager: Res<DialogManager>,
+    playtime: Res<PlayTime>,
+    background_query: Query<&Background>,
+) {
+    for event in events.read() {
+        let data = capture_save(
+            &state,
+            &history,
+            &choice_state,
+            &variables,
+            &dialog_manager,
+            &playtime,
+            background_query.get_single().ok(),
+        );
+        match write_slot(event.slot, &data) {
+            Ok(()) => info!("Saved {} ({} stage {})", event.slot, data.scene, data.stage),
+            Err(err) => error!("Failed to save {}: {:#}", event.slot, err),
+        }
+    }
+}
+
+/// auto-save เมื่อมีคำขอจาก `change_scene` หรือก่อนแสดงตัวเลือก (รอให้บรรทัดใหม่แสดงก่อน)
+pub fn handle_auto_save(
+    mut state: ResMut<VNState>,
+    mut rotation: ResMut<AutoSaveRotation>,
+    history: Res<DialogHistory>,
+    choice_state: Res<ChoiceState>,
+    variables: Res<StoryVariables>,
+    dialog_manager: Res<DialogManager>,
+    playtime: Res<PlayTime>,
+    background_query: Query<&Background>,
+) {
+    if !state.autosave_pending || dialog_manager.is_processing() {
+        return;
+    }
+    state.autosave_pending = false;
+
+    let data = capture_save(
+        &state,
+        &history,
+        &choice_state,
+        &variables,
+        &dialog_manager,
+        &playtime,
+        background_query.get_single().ok(),
+    );
+    let slot = rotation.next_slot();
+    match write_slot(slot, &data) {
+        Ok(()) => info!("Saved {} ({} stage {})", slot, data.scene, data.stage),
+        Err(err) => error!("Failed to save {}: {:#}", slot, err),
+    }
+}
+
+/// quick save (F5) และ quick load (F9)
+pub fn handle_quick_save_input(
+    actions: Res<ActionState>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+) {
+    if actions.just_pressed(InputAction::QuickSave) {
+        save_events.send(SaveGameEvent { slot: SaveSlot::Quick });
+    }
+    if actions.just_pressed(InputAction::QuickLoad) {
+        if read_meta(SaveSlot::Quick).is_some() {
+            load_events.send(LoadGameEvent { slot: SaveSlot::Quick });
+        } else {
+            warn!("No quick save to load");
+        }
+    }
+}
+
+/// ล้างตัวแปร ประวัติ backlog ตัวเลือก call stack และเวลาเล่น แล้วเข้าเกมที่ scene แรกผ่านหน้า Loading
+/// (ถ้า story.json ยังโหลดไม่เสร็จ `register_story_scenes` จะตั้ง scene แรกให้ทีหลัง)
+pub fn handle_new_game_events(
+    mut events: EventReader<NewGameEvent>,
+    story: Res<StoryResource>,
+    manifests: Res<Assets<StoryManifest>>,
+    mut state: ResMut<VNState>,
+    mut dialog_resource: ResMut<DialogResource>,
+    mut history: ResMut<DialogHistory>,
+    mut backlog: ResMut<Backlog>,
+    mut choice_state: ResMut<ChoiceState>,
+    mut variables: ResMut<StoryVariables>,
+    mut playtime: ResMut<PlayTime>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+
+    variables.clear();
+    history.clear();
+    backlog.clear();
+    choice_state.deactivate();
+    choice_state.history.clear();
+    playtime.seconds = 0.0;
+    // `return` ในรอบใหม่ต้องไม่กลับไปหาผู้เรียกของรอบก่อน
+    state.call_stack.clear();
+
+    let start = manifests.get(&story.manifest).map(|manifest| manifest.start.clone());
+    if !start.is_some_and(|start| dialog_resource.change_scene(&start, &mut state)) {
+        dialog_resource.current_scene = None;
+        state.current_scene.clear();
+        state.current_scene_handle = None;
+        state.force_stage(0);
+    }
+    state.autosave_pending = false;
+
+    info!("New game ({})", state.current_scene);
+    change_events.send(ChangeStateEvent {
+        new_state: GameState::Loading,
+    });
+}
+
+/// โหลด slot แล้วเข้าเกมผ่านหน้า Loading (ซึ่งล้าง scene เดิมก่อน)
+pub fn handle_load_events(
+    mut events: EventReader<LoadGameEvent>,
+    mut state: ResMut<VNState>,
+    mut dialog_resource: ResMut<DialogResource>,
+    mut history: ResMut<DialogHistory>,
+    mut backlog: ResMut<Backlog>,
+    mut choice_state: ResMut<ChoiceState>,
+    mut variables: ResMut<StoryVariables>,
+    mut playtime: ResMut<PlayTime>,
+    mut transition_state: ResMut<TransitionState>,
+    mut restore: ResMut<SceneRestore>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+
+    let data = match read_slot(event.slot) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Failed to load {}: {:#}", event.slot, err);
+            return;
+        }
+    };
+    if !dialog_resource.change_scene(&data.scene, &mut state) {
+        error!("Save {} refers to unknown scene `{}`", event.slot, data.scene);
+        return;
+    }
+
+    state.force_stage(data.stage);
+    state.change_language(data.language);
+    state.call_stack = data.call_stack;
+    // การโหลดไม่นับเป็นการเปลี่ยน scene
+    state.autosave_pending = false;
+    *history = data.history;
+    // backlog ไม่อยู่ใน save จึงเริ่มใหม่จากจุดที่โหลด
+    backlog.clear();
+    choice_state.deactivate();
+    choice_state.history = data.choices;
+    *variables = data.variables;
+    playtime.seconds = data.meta.playtime;
+
+    // ฉากขึ้นทันทีเหมือนตอน save ไม่เล่น transition ของ entry ซ้ำ
+    transition_state.skip_entry(&state.current_scene, state.stage);
+    restore.request(data.background);
+
+    info!("Loaded {} ({} stage {})", event.slot, state.current_scene, state.stage);
+    change_events.send(ChangeStateEvent {
+        new_state: GameState::Loading,
+    });
+}
+
+/// จัดพื้นหลังและตัวละครให้ตรงกับตอน save เมื่อ scene ถูกสร้างใหม่แล้ว
+pub fn restore_loaded_scene(
+    asset_server: Res<AssetServer>,
+    state: Res<VNState>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+    mut restore: ResMut<SceneRestore>,
+    mut background_query: Query<(&mut Handle<Image>, &mut Background)>,
+    mut character_query: Query<(&mut Transform, &mut Sprite, &mut Visibility, &CharacterSprite)>,
+) {
+    if !restore.pending {
+        return;
+    }
+    let Some(scene) = state
+        .current_scene_handle
+        .as_ref()
+        .and_then(|handle| dialog_scenes.get(handle))
+    else {
+        return;
+    };
+    let Some(entry) = scene.entries.get(state.stage) else {
+        restore.pending = false;
+        return;
+    };
+    // รอ `setup_characters` และพื้นหลังของ OnEnter(InGame)
+    let has_sprites = scene.characters.iter().any(|character| !character.sprite.is_empty());
+    if (has_sprites && character_query.is_empty()) || background_query.is_empty() {
+        return;
+    }
+
+    let saved_background = restore.background.take();
+    if let (Some(path), Ok((mut handle, mut background))) = (saved_background, background_query.get_single_mut()) {
+        *handle = texture(&path).load::<Image>(&asset_server);
+        background.current_path = path;
+    }
+    place_characters(entry, &mut character_query);
+    restore.pending = false;
+}
@@ -0,0 +1,3 @@
+pub mod data;
+pub mod manager;
+pub mod read_log;
\ No newline at end of file
@@ -0,0 +1,113 @@
+use anyhow::Context;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use crate::core::resources::{DialogManager, VNState};
+use crate::util::paths::data_dir;
+
+/// ช่วงเวลาขั้นต่ำระหว่างการเขียนไฟล์ (ตอนปิดเกมเขียนทันที)
+const READ_LOG_SAVE_INTERVAL: f32 = 5.0;
+
+/// entry ที่เคยอ่านจบแล้ว รวมทุกรอบที่เล่น เก็บแยกจากไฟล์ save
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct ReadLog {
+    scenes: BTreeMap<String, BTreeSet<usize>>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl ReadLog {
+    pub fn is_read(&self, scene: &str, stage: usize) -> bool {
+        self.scenes.get(scene).is_some_and(|stages| stages.contains(&stage))
+    }
+
+    pub fn mark_read(&mut self, scene: &str, stage: usize) {
+        if self.scenes.entry(scene.to_string()).or_default().insert(stage) {
+            self.dirty = true;
+        }
+    }
+
+    /// อ่านจากไฟล์ ถ้ายังไม่มีหรืออ่านไม่ได้เริ่มใหม่
+    pub fn load() -> Self {
+        let path = match read_log_path() {
+            Ok(path) => path,
+            Err(err) => {
+                warn!("{:#}", err);
+                return Self::default();
+            }
+        };
+        let Ok(bytes) = fs::read(&path) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+            warn!("Invalid read log {}: {}", path.display(), err);
+            Self::default()
+        })
+    }
+
+    /// เขียนลงไฟล์เมื่อมีบรรทัดใหม่ที่อ่านแล้ว
+    pub fn write(&mut self) -> anyhow::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = read_log_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+        }
+
+        let json = serde_json::to_vec(self)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json).with_context(|| format!("Cannot write {}", temp.display()))?;
+        fs::rename(&temp, &path).with_context(|| format!("Cannot write {}", path.display()))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn read_log_path() -> anyhow::Result<PathBuf> {
+    let dir = data_dir().context("Cannot find a user data directory for the read log")?;
+    Ok(dir.join("read.json"))
+}
+
+/// บรรทัดนับว่าอ่านแล้วเมื่อผู้เล่นไปต่อจากบรรทัดนั้น (บรรทัดที่แสดงอยู่ยังเป็น "ใหม่")
+pub fn track_read_lines(
+    state: Res<VNState>,
+    dialog_manager: Res<DialogManager>,
+    mut read_log: ResMut<ReadLog>,
+    mut shown: Local<Option<(String, usize)>>,
+) {
+    if state.should_reset_dialog() || dialog_manager.is_processing() {
+        return;
+    }
+    if shown
+        .as_ref()
+        .is_some_and(|(scene, stage)| *scene == state.current_scene && *stage == state.stage)
+    {
+        return;
+    }
+
+    if let Some((scene, stage)) = shown.take() {
+        read_log.mark_read(&scene, stage);
+    }
+    *shown = Some((state.current_scene.clone(), state.stage));
+}
+
+pub fn save_read_log(
+    time: Res<Time>,
+    mut exit_events: EventReader<AppExit>,
+    mut read_log: ResMut<ReadLog>,
+    mut elapsed: Local<f32>,
+) {
+    *elapsed += time.delta_seconds();
+    let exiting = exit_events.read().count() > 0;
+    if !exiting && *elapsed < READ_LOG_SAVE_INTERVAL {
+        return;
+    }
+    *elapsed = 0.0;
+
+    if let Err(err) = read_log.write() {
+        error!("Failed to save read log: {:#}", err);
+    }
+}
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use crate::types::DialogScene;
+use crate::types::{DialogEntry, DialogScene};
 use crate::core::resources::VNState;
 use crate::util::identifier::texture;
 
@@ -100,6 +100,35 @@ fn position_from_string(position: &str) -> Vec3 {
     }
 }
 
+/// วางตัวละครที่ตำแหน่งสุดท้ายของ entry ทันทีโดยไม่เลื่อนเข้ามา (ใช้ตอนโหลด save)
+pub fn place_characters(
+    entry: &DialogEntry,
+    character_query: &mut Query<(&mut Transform, &mut Sprite, &mut Visibility, &CharacterSprite)>,
+) {
+    for (mut transform, mut sprite, mut visibility, character) in character_query.iter_mut() {
+        let Some(char_state) = entry
+            .character_states
+            .iter()
+            .find(|char_state| char_state.name == character.name)
+        else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        let target_pos = position_from_string(&char_state.position);
+        if char_state.highlight || character.name == entry.character {
+            transform.translation = target_pos + Vec3::Y * 20.0;
+            transform.scale = HIGHLIGHT_SCALE;
+            sprite.color = HIGHLIGHT_COLOR;
+        } else {
+            transform.translation = target_pos;
+            transform.scale = DIMMED_SCALE;
+            sprite.color = DIMMED_COLOR;
+        }
+    }
+}
+
 pub fn update_characters(
     state: Res<VNState>,
     dialog_scenes: Res<Assets<DialogScene>>,
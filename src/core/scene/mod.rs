@@ -1,2 +1,3 @@
 pub mod background;
-pub mod character;
\ No newline at end of file
+pub mod character;
+pub mod transition;
\ No newline at end of file
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use crate::core::resources::VNState;
+use crate::core::scene::background::Background;
+use crate::types::{DialogScene, DialogTransition, TransitionType};
+
+/// Overlay สีดำสำหรับ fade (อยู่ใต้ dialog box แต่ทับฉากและตัวละคร)
+#[derive(Component)]
+pub struct TransitionOverlay;
+
+/// สำเนาของพื้นหลังเดิมที่ค่อยๆ หายไประหว่าง crossfade / wipe
+#[derive(Component)]
+pub struct TransitionLayer;
+
+struct ActiveTransition {
+    kind: TransitionType,
+    timer: Timer,
+    image_size: Option<Vec2>,
+}
+
+#[derive(Resource, Default)]
+pub struct TransitionState {
+    active: Option<ActiveTransition>,
+    /// fade_out จบแล้วแต่ยังค้างจอดำไว้จนกว่าจะถึง entry ถัดไป
+    holding_black: bool,
+    last_entry: Option<(String, usize)>,
+}
+
+impl TransitionState {
+    /// ระหว่างเล่น transition จะไม่รับ input
+    pub fn is_playing(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// ถือว่า entry นี้เล่น transition ไปแล้ว (ใช้ตอนโหลด save ให้ฉากขึ้นทันที)
+    pub fn skip_entry(&mut self, scene: &str, stage: usize) {
+        self.active = None;
+        self.holding_black = false;
+        self.last_entry = Some((scene.to_string(), stage));
+    }
+}
+
+const TRANSITION_Z_LAYER: f32 = 5.0;
+const TRANSITION_LAYER_Z: f32 = 0.5;
+const RELEASE_BLACK_DURATION: f32 = 0.5;
+
+/// เริ่ม transition เมื่อ entry ปัจจุบันเปลี่ยน (ต้องทำงานก่อน `update_background`)
+pub fn start_entry_transition(
+    mut commands: Commands,
+    state: Res<VNState>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+    images: Res<Assets<Image>>,
+    mut transition_state: ResMut<TransitionState>,
+    background_query: Query<(&Handle<Image>, &Transform), With<Background>>,
+    overlay_query: Query<Entity, With<TransitionOverlay>>,
+    layer_query: Query<Entity, With<TransitionLayer>>,
+) {
+    let Some(scene) = state
+        .current_scene_handle
+        .as_ref()
+        .and_then(|handle| dialog_scenes.get(handle))
+    else {
+        return;
+    };
+
+    let current_entry = (state.current_scene.clone(), state.stage);
+    if transition_state.last_entry.as_ref() == Some(&current_entry) {
+        return;
+    }
+    transition_state.last_entry = Some(current_entry);
+
+    let transition = scene
+        .entries
+        .get(state.stage)
+        .and_then(|entry| entry.transition.clone())
+        .or_else(|| {
+            transition_state.holding_black.then_some(DialogTransition {
+                type_name: TransitionType::FadeIn,
+                duration: RELEASE_BLACK_DURATION,
+            })
+        });
+
+    let Some(transition) = transition else {
+        return;
+    };
+
+    // ล้าง transition ที่ยังเล่นค้างอยู่ก่อนเริ่มอันใหม่
+    for entity in layer_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    match transition.type_name {
+        TransitionType::FadeIn | TransitionType::FadeOut => {
+            let start_alpha = if transition.type_name == TransitionType::FadeIn { 1.0 } else { 0.0 };
+            if overlay_query.is_empty() {
+                spawn_overlay(&mut commands, start_alpha);
+            }
+            transition_state.holding_black = false;
+        }
+        _ => {
+            for entity in overlay_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            transition_state.holding_black = false;
+
+            if let Ok((texture, transform)) = background_query.get_single() {
+                commands.spawn((
+                    SpriteBundle {
+                        texture: texture.clone(),
+                        transform: transform.with_translation(Vec3::new(
+                            transform.translation.x,
+                            transform.translation.y,
+                            TRANSITION_LAYER_Z,
+                        )),
+                        ..default()
+                    },
+                    TransitionLayer,
+                    Name::new("transition_layer"),
+                ));
+            }
+        }
+    }
+
+    let image_size = background_query
+        .get_single()
+        .ok()
+        .and_then(|(texture, _)| images.get(texture))
+        .map(|image| image.size().as_vec2());
+
+    transition_state.active = Some(ActiveTransition {
+        kind: transition.type_name,
+        timer: Timer::from_seconds(transition.duration.max(0.0), TimerMode::Once),
+        image_size,
+    });
+}
+
+fn spawn_overlay(commands: &mut Commands, alpha: f32) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::srgba(0.0, 0.0, 0.0, alpha).into(),
+            z_index: ZIndex::Global(TRANSITION_Z_LAYER as i32),
+            ..default()
+        },
+        TransitionOverlay,
+        Name::new("transition_overlay"),
+    ));
+}
+
+pub fn update_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut transition_state: ResMut<TransitionState>,
+    mut overlay_query: Query<(Entity, &mut BackgroundColor), With<TransitionOverlay>>,
+    mut layer_query: Query<(Entity, &mut Sprite, &mut Transform), With<TransitionLayer>>,
+) {
+    let Some(active) = transition_state.active.as_mut() else {
+        return;
+    };
+
+    active.timer.tick(time.delta());
+    let progress = if active.timer.duration().is_zero() { 1.0 } else { active.timer.fraction() };
+    let finished = active.timer.finished();
+    let kind = active.kind;
+    let image_size = active.image_size;
+
+    match kind {
+        TransitionType::FadeIn | TransitionType::FadeOut => {
+            let alpha = if kind == TransitionType::FadeIn { 1.0 - progress } else { progress };
+            for (entity, mut color) in overlay_query.iter_mut() {
+                if finished && kind == TransitionType::FadeIn {
+                    commands.entity(entity).despawn_recursive();
+                } else {
+                    *color = Color::srgba(0.0, 0.0, 0.0, alpha).into();
+                }
+            }
+        }
+        TransitionType::Crossfade => {
+            for (_, mut sprite, _) in layer_query.iter_mut() {
+                sprite.color = Color::srgba(1.0, 1.0, 1.0, 1.0 - progress);
+            }
+        }
+        wipe => {
+            for (_, mut sprite, mut transform) in layer_query.iter_mut() {
+                match image_size {
+                    Some(size) => apply_wipe(wipe, progress, size, &mut sprite, &mut transform),
+                    // ยังไม่รู้ขนาดรูป ใช้ crossfade แทน
+                    None => sprite.color = Color::srgba(1.0, 1.0, 1.0, 1.0 - progress),
+                }
+            }
+        }
+    }
+
+    if finished {
+        for (entity, _, _) in layer_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        transition_state.holding_black = kind == TransitionType::FadeOut;
+        transition_state.active = None;
+    }
+}
+
+/// ตัดภาพพื้นหลังเดิมให้เหลือเฉพาะส่วนที่ยังไม่ถูกกวาด
+fn apply_wipe(kind: TransitionType, progress: f32, size: Vec2, sprite: &mut Sprite, transform: &mut Transform) {
+    let remaining = 1.0 - progress;
+    let scale = transform.scale.truncate();
+
+    let (anchor, rect, offset) = match kind {
+        TransitionType::WipeLeft => (
+            Anchor::CenterLeft,
+            Rect::new(0.0, 0.0, size.x * remaining, size.y),
+            Vec2::new(-size.x / 2.0, 0.0),
+        ),
+        TransitionType::WipeRight => (
+            Anchor::CenterRight,
+            Rect::new(size.x * progress, 0.0, size.x, size.y),
+            Vec2::new(size.x / 2.0, 0.0),
+        ),
+        TransitionType::WipeUp => (
+            Anchor::TopCenter,
+            Rect::new(0.0, 0.0, size.x, size.y * remaining),
+            Vec2::new(0.0, size.y / 2.0),
+        ),
+        _ => (
+            Anchor::BottomCenter,
+            Rect::new(0.0, size.y * progress, size.x, size.y),
+            Vec2::new(0.0, -size.y / 2.0),
+        ),
+    };
+
+    sprite.anchor = anchor;
+    sprite.rect = Some(rect);
+    let position = offset * scale;
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
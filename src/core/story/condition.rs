@@ -0,0 +1,541 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::core::story::variables::{StoryValue, StoryVariables};
+
+/// Condition expression เช่น `affection.miki >= 3 && !met_yui`
+///
+/// รองรับ `&&` / `and`, `||` / `or`, `!` / `not`, วงเล็บ,
+/// การเปรียบเทียบ `== != < <= > >=` และค่าคงที่ int, bool, "string"
+///
+/// ใน dialog file เขียนเป็นข้อความ และถูก parse ครั้งเดียวตอนโหลด scene (condition ที่ผิดทำให้โหลด scene ไม่ผ่าน)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Condition {
+    Value(Operand),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Compare(Operand, CompareOp, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Variable(String),
+    Literal(StoryValue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionError {
+    pub expression: String,
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid condition `{}` at column {}: {}",
+            self.expression,
+            self.position + 1,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConditionError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    Not,
+    And,
+    Or,
+    Op(CompareOp),
+    LParen,
+    RParen,
+}
+
+impl Condition {
+    pub fn parse(expression: &str) -> Result<Self, ConditionError> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser {
+            expression,
+            tokens,
+            index: 0,
+        };
+
+        let condition = parser.parse_or()?;
+        if parser.index < parser.tokens.len() {
+            return Err(parser.error("unexpected token"));
+        }
+        Ok(condition)
+    }
+
+    pub fn evaluate(&self, variables: &StoryVariables) -> bool {
+        match self {
+            Condition::Value(operand) => resolve(operand, variables)
+                .map(|value| value.is_truthy())
+                .unwrap_or(false),
+            Condition::Not(inner) => !inner.evaluate(variables),
+            Condition::And(left, right) => left.evaluate(variables) && right.evaluate(variables),
+            Condition::Or(left, right) => left.evaluate(variables) || right.evaluate(variables),
+            Condition::Compare(left, op, right) => {
+                compare(resolve(left, variables), *op, resolve(right, variables))
+            }
+        }
+    }
+
+    /// ชื่อตัวแปรทั้งหมดที่ expression อ้างถึง
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_variables(&mut names);
+        names
+    }
+
+    fn collect_variables<'a>(&'a self, names: &mut Vec<&'a str>) {
+        let mut push = |operand: &'a Operand| {
+            if let Operand::Variable(name) = operand {
+                names.push(name.as_str());
+            }
+        };
+
+        match self {
+            Condition::Value(operand) => push(operand),
+            Condition::Compare(left, _, right) => {
+                push(left);
+                push(right);
+            }
+            Condition::Not(inner) => inner.collect_variables(names),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.collect_variables(names);
+                right.collect_variables(names);
+            }
+        }
+    }
+}
+
+/// ประเมินเงื่อนไขทั้งหมด (ต้องเป็นจริงทุกข้อ)
+pub fn evaluate_all(conditions: &[Condition], variables: &StoryVariables) -> bool {
+    conditions.iter().all(|condition| condition.evaluate(variables))
+}
+
+/// เขียนกลับเป็น expression ที่ parse ได้ค่าเดิม (ใส่วงเล็บเฉพาะที่จำเป็น)
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Value(operand) => write!(f, "{}", operand),
+            Condition::Compare(left, op, right) => write!(f, "{} {} {}", left, op, right),
+            Condition::Not(inner) => match inner.as_ref() {
+                Condition::And(..) | Condition::Or(..) => write!(f, "!({})", inner),
+                _ => write!(f, "!{}", inner),
+            },
+            // `&&` / `||` จับกลุ่มจากซ้าย ตัวขวาที่เป็น operator เดียวกันจึงต้องใส่วงเล็บ
+            Condition::And(left, right) => {
+                let left = match left.as_ref() {
+                    Condition::Or(..) => format!("({})", left),
+                    _ => left.to_string(),
+                };
+                match right.as_ref() {
+                    Condition::And(..) | Condition::Or(..) => write!(f, "{} && ({})", left, right),
+                    _ => write!(f, "{} && {}", left, right),
+                }
+            }
+            Condition::Or(left, right) => match right.as_ref() {
+                Condition::Or(..) => write!(f, "{} || ({})", left, right),
+                _ => write!(f, "{} || {}", left, right),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Variable(name) => f.write_str(name),
+            Operand::Literal(StoryValue::Str(value)) => write!(f, "\"{}\"", value),
+            Operand::Literal(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Eq => "==",
+            CompareOp::NotEq => "!=",
+            CompareOp::Less => "<",
+            CompareOp::LessEq => "<=",
+            CompareOp::Greater => ">",
+            CompareOp::GreaterEq => ">=",
+        })
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.to_string()
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = ConditionError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        Condition::parse(&expression)
+    }
+}
+
+fn resolve<'a>(operand: &'a Operand, variables: &'a StoryVariables) -> Option<&'a StoryValue> {
+    match operand {
+        Operand::Variable(name) => variables.get(name),
+        Operand::Literal(value) => Some(value),
+    }
+}
+
+/// ตัวแปรที่ยังไม่ถูกตั้งค่าจะถูกมองเป็นค่าว่างของชนิดฝั่งตรงข้าม (0, false, "")
+fn compare(left: Option<&StoryValue>, op: CompareOp, right: Option<&StoryValue>) -> bool {
+    let (left, right) = match (left, right) {
+        (Some(l), Some(r)) => (l.clone(), r.clone()),
+        (Some(l), None) => (l.clone(), empty_like(l)),
+        (None, Some(r)) => (empty_like(r), r.clone()),
+        (None, None) => return matches!(op, CompareOp::Eq | CompareOp::LessEq | CompareOp::GreaterEq),
+    };
+
+    let ordering = match (&left, &right) {
+        (StoryValue::Int(l), StoryValue::Int(r)) => l.cmp(r),
+        (StoryValue::Str(l), StoryValue::Str(r)) => l.cmp(r),
+        (StoryValue::Bool(l), StoryValue::Bool(r)) => l.cmp(r),
+        (StoryValue::Bool(b), StoryValue::Int(i)) => (*b as i64).cmp(i),
+        (StoryValue::Int(i), StoryValue::Bool(b)) => i.cmp(&(*b as i64)),
+        _ => return op == CompareOp::NotEq,
+    };
+
+    match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::NotEq => ordering.is_ne(),
+        CompareOp::Less => ordering.is_lt(),
+        CompareOp::LessEq => ordering.is_le(),
+        CompareOp::Greater => ordering.is_gt(),
+        CompareOp::GreaterEq => ordering.is_ge(),
+    }
+}
+
+fn empty_like(value: &StoryValue) -> StoryValue {
+    match value {
+        StoryValue::Bool(_) => StoryValue::Bool(false),
+        StoryValue::Int(_) => StoryValue::Int(0),
+        StoryValue::Str(_) => StoryValue::Str(String::new()),
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, ConditionError> {
+    let error = |position: usize, message: &str| ConditionError {
+        expression: expression.to_string(),
+        position,
+        message: message.to_string(),
+    };
+
+    let chars: Vec<(usize, char)> = expression.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (position, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+            }
+            '(' => {
+                tokens.push((position, Token::LParen));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((position, Token::RParen));
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push((position, Token::And));
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push((position, Token::Or));
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push((position, Token::Op(CompareOp::Eq)));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push((position, Token::Op(CompareOp::NotEq)));
+                i += 2;
+            }
+            '!' => {
+                tokens.push((position, Token::Not));
+                i += 1;
+            }
+            '<' | '>' => {
+                let op = match (c, next == Some('=')) {
+                    ('<', true) => CompareOp::LessEq,
+                    ('<', false) => CompareOp::Less,
+                    (_, true) => CompareOp::GreaterEq,
+                    (_, false) => CompareOp::Greater,
+                };
+                tokens.push((position, Token::Op(op)));
+                i += if next == Some('=') { 2 } else { 1 };
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => value.push(*c),
+                        None => return Err(error(position, "unterminated string")),
+                    }
+                    i += 1;
+                }
+                tokens.push((position, Token::Str(value)));
+                i += 1;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i].1.is_ascii_digit() {
+                    i += 1;
+                }
+                let literal: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                let value = literal
+                    .parse::<i64>()
+                    .map_err(|_| error(position, "integer out of range"))?;
+                tokens.push((position, Token::Int(value)));
+            }
+            c if is_ident_char(c) => {
+                let start = i;
+                while i < chars.len() && (is_ident_char(chars[i].1) || chars[i].1 == '.') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                let token = match word.as_str() {
+                    "true" => Token::Bool(true),
+                    "false" => Token::Bool(false),
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                };
+                tokens.push((position, token));
+            }
+            _ => return Err(error(position, &format!("unexpected character `{}`", c))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(_, token)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    fn error(&self, message: &str) -> ConditionError {
+        let position = self
+            .tokens
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.expression.len());
+        ConditionError {
+            expression: self.expression.to_string(),
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, ConditionError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            let right = self.parse_and()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, ConditionError> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            let right = self.parse_unary()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, ConditionError> {
+        if self.peek() == Some(&Token::Not) {
+            self.advance();
+            let inner = self.parse_unary()?;
+            return Ok(Condition::Not(Box::new(inner)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Condition, ConditionError> {
+        if self.peek() == Some(&Token::LParen) {
+            self.advance();
+            let inner = self.parse_or()?;
+            if self.advance() != Some(Token::RParen) {
+                self.index -= 1;
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(inner);
+        }
+
+        let left = self.parse_operand()?;
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.advance();
+            let right = self.parse_operand()?;
+            return Ok(Condition::Compare(left, op, right));
+        }
+        Ok(Condition::Value(left))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, ConditionError> {
+        let operand = match self.peek() {
+            Some(Token::Ident(name)) => Operand::Variable(name.clone()),
+            Some(Token::Int(value)) => Operand::Literal(StoryValue::Int(*value)),
+            Some(Token::Str(value)) => Operand::Literal(StoryValue::Str(value.clone())),
+            Some(Token::Bool(value)) => Operand::Literal(StoryValue::Bool(*value)),
+            Some(_) => return Err(self.error("expected a variable or value")),
+            None => return Err(self.error("unexpected end of condition")),
+        };
+        self.advance();
+        Ok(operand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str) -> Box<Condition> {
+        Box::new(Condition::Value(Operand::Variable(name.to_string())))
+    }
+
+    fn evaluate(expression: &str, variables: &StoryVariables) -> bool {
+        Condition::parse(expression).unwrap().evaluate(variables)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            Condition::parse("a || b && c").unwrap(),
+            Condition::Or(variable("a"), Box::new(Condition::And(variable("b"), variable("c"))))
+        );
+        assert_eq!(
+            Condition::parse("a or b and c").unwrap(),
+            Condition::parse("a || b && c").unwrap()
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            Condition::parse("(a || b) && c").unwrap(),
+            Condition::And(Box::new(Condition::Or(variable("a"), variable("b"))), variable("c"))
+        );
+    }
+
+    #[test]
+    fn not_and_keyword_not() {
+        let mut variables = StoryVariables::default();
+        variables.set_bool("met_yui", true);
+        assert_eq!(Condition::parse("!met_yui").unwrap(), Condition::Not(variable("met_yui")));
+        assert_eq!(Condition::parse("not met_yui").unwrap(), Condition::Not(variable("met_yui")));
+        assert!(!evaluate("!met_yui", &variables));
+        assert!(evaluate("!!met_yui", &variables));
+        assert!(evaluate("!(met_yui && missing)", &variables));
+        assert!(evaluate("not missing", &variables));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for expression in [
+            "a || b && c",
+            "(a || b) && c",
+            "a && (b && c)",
+            "a || (b || c)",
+            "!(a || b) && !c",
+            "affection.miki >= 3 && !met_yui",
+            "name == \"Miki san\" || count != -2",
+            "flag == true",
+        ] {
+            let condition = Condition::parse(expression).unwrap();
+            assert_eq!(Condition::parse(&condition.to_string()).unwrap(), condition, "{}", expression);
+        }
+        assert_eq!(Condition::parse("(a)&&(b||c)").unwrap().to_string(), "a && (b || c)");
+    }
+
+    #[test]
+    fn mixed_type_comparisons() {
+        let mut variables = StoryVariables::default();
+        variables.set_bool("flag", true);
+        variables.set_int("count", 2);
+        variables.set_str("name", "Miki");
+
+        // bool เทียบกับ int ได้ (true = 1)
+        assert!(evaluate("flag == 1", &variables));
+        assert!(evaluate("count > flag", &variables));
+        // string เทียบกับชนิดอื่นไม่เท่ากันเสมอ
+        assert!(evaluate("name != 2", &variables));
+        assert!(!evaluate("name == 2", &variables));
+        assert!(!evaluate("name > 2", &variables));
+        // ตัวแปรที่ยังไม่ตั้งค่าเป็นค่าว่างของชนิดฝั่งตรงข้าม
+        assert!(evaluate("missing == 0", &variables));
+        assert!(evaluate("missing == \"\"", &variables));
+        assert!(evaluate("missing < count", &variables));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let position = |expression: &str| Condition::parse(expression).unwrap_err().position;
+        assert_eq!(position("a && "), 5);
+        assert_eq!(position("a == \"x"), 5);
+        assert_eq!(position("(a || b"), 7);
+        assert_eq!(position("a b"), 2);
+        assert_eq!(position("a # b"), 2);
+        assert_eq!(position("a == =="), 5);
+        assert_eq!(Condition::parse("a # b").unwrap_err().message, "unexpected character `#`");
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::asset::{AssetLoader, LoadContext, AsyncReadExt};
+use bevy::utils::ConditionalSendFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+/// ไฟล์ manifest ของเรื่อง (อยู่ใน `assets/`)
+pub const STORY_MANIFEST: &str = "story.json";
+
+/// `story.json` - รายชื่อ scene ทั้งหมดของเรื่องและ scene เริ่มต้น
+///
+/// ```json
+/// {
+///   "title": { "english": "Helium", "thai": "ฮีเลียม" },
+///   "start": "intro",
+///   "scenes": [
+///     { "name": "intro", "path": "intro.dialog.json", "chapter": { "english": "Prologue" },
+///       "preload": ["textures/backgrounds/city_street.png"] }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize, Serialize, TypePath, Asset, Clone)]
+pub struct StoryManifest {
+    #[serde(default)]
+    pub title: HashMap<String, String>,
+    pub start: String,
+    pub scenes: Vec<SceneInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SceneInfo {
+    /// ชื่อที่ใช้กับ `change_scene` และ `scene#label`
+    pub name: String,
+    /// path ใน `assets/dialogs`
+    pub path: String,
+    #[serde(default)]
+    pub chapter: HashMap<String, String>,
+    /// asset ที่โหลดไว้ล่วงหน้าเมื่อเข้า scene นี้ (path ใน `assets/`)
+    #[serde(default)]
+    pub preload: Vec<String>,
+}
+
+impl StoryManifest {
+    pub fn scene(&self, name: &str) -> Option<&SceneInfo> {
+        self.scenes.iter().find(|scene| scene.name == name)
+    }
+
+    /// ชื่อบทของ scene ในภาษาที่เลือก
+    pub fn chapter(&self, scene: &str, language: &str) -> Option<&str> {
+        self.scene(scene)?.chapter.get(language).map(String::as_str)
+    }
+
+    /// ตรวจว่าชื่อ scene ไม่ซ้ำและ scene เริ่มต้นมีอยู่จริง
+    pub fn check(&self) -> anyhow::Result<()> {
+        let mut names = HashSet::new();
+        for scene in &self.scenes {
+            if !names.insert(scene.name.as_str()) {
+                anyhow::bail!("scene `{}` is listed more than once", scene.name);
+            }
+        }
+        if self.scene(&self.start).is_none() {
+            anyhow::bail!("start scene `{}` is not in `scenes`", self.start);
+        }
+        Ok(())
+    }
+}
+
+/// Handle ของ manifest และ asset ที่ preload ไว้ของ scene ปัจจุบัน
+#[derive(Resource)]
+pub struct StoryResource {
+    pub manifest: Handle<StoryManifest>,
+    pub preloaded: Vec<UntypedHandle>,
+}
+
+/// Story manifest asset loader
+#[derive(Default)]
+pub struct StoryManifestLoader;
+
+impl AssetLoader for StoryManifestLoader {
+    type Asset = StoryManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture + Future<Output = Result<<Self as AssetLoader>::Asset, <Self as AssetLoader>::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let manifest: StoryManifest = serde_json::from_slice(&bytes)?;
+            manifest.check()?;
+            Ok(manifest)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["story.json"]
+    }
+}
@@ -0,0 +1,3 @@
+pub mod variables;
+pub mod condition;
+pub mod manifest;
\ No newline at end of file
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// ค่าของตัวแปรในเนื้อเรื่อง (bool, int, string)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StoryValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl StoryValue {
+    /// ค่าที่ถือว่าเป็นจริงเมื่อใช้เป็นเงื่อนไขเดี่ยวๆ
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            StoryValue::Bool(value) => *value,
+            StoryValue::Int(value) => *value != 0,
+            StoryValue::Str(value) => !value.is_empty(),
+        }
+    }
+
+    /// แปลงข้อความจาก script เป็นค่า (true/false, ตัวเลข, หรือ string)
+    pub fn parse(raw: &str) -> Self {
+        let trimmed = raw.trim();
+        match trimmed {
+            "true" => return StoryValue::Bool(true),
+            "false" => return StoryValue::Bool(false),
+            _ => {}
+        }
+
+        if let Ok(value) = trimmed.parse::<i64>() {
+            return StoryValue::Int(value);
+        }
+
+        let unquoted = trimmed
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(trimmed);
+        StoryValue::Str(unquoted.to_string())
+    }
+}
+
+impl fmt::Display for StoryValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoryValue::Bool(value) => write!(f, "{}", value),
+            StoryValue::Int(value) => write!(f, "{}", value),
+            StoryValue::Str(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// ตัวแปรของเนื้อเรื่องที่คงอยู่ตลอดการเล่น (อยู่คู่กับ VNState)
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct StoryVariables {
+    values: HashMap<String, StoryValue>,
+}
+
+impl StoryVariables {
+    pub fn get(&self, name: &str) -> Option<&StoryValue> {
+        self.values.get(name)
+    }
+
+    pub fn set(&mut self, name: &str, value: StoryValue) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.set(name, StoryValue::Bool(value));
+    }
+
+    pub fn set_int(&mut self, name: &str, value: i64) {
+        self.set(name, StoryValue::Int(value));
+    }
+
+    pub fn set_str(&mut self, name: &str, value: &str) {
+        self.set(name, StoryValue::Str(value.to_string()));
+    }
+
+    pub fn get_bool(&self, name: &str) -> bool {
+        self.get(name).map(StoryValue::is_truthy).unwrap_or(false)
+    }
+
+    pub fn get_int(&self, name: &str) -> i64 {
+        match self.get(name) {
+            Some(StoryValue::Int(value)) => *value,
+            Some(StoryValue::Bool(value)) => *value as i64,
+            _ => 0,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<StoryValue> {
+        self.values.remove(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &StoryValue)> {
+        self.values.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
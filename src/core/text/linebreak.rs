@@ -0,0 +1,42 @@
+use icu_segmenter::LineSegmenter;
+
+/// Zero-width space: จุดที่ตัดบรรทัดได้โดยไม่มีช่องว่างให้เห็น
+pub const ZWSP: char = '\u{200B}';
+
+thread_local! {
+    // LineSegmenter ไม่เป็น `Sync` จึงสร้างไว้ต่อ thread
+    static SEGMENTER: LineSegmenter = LineSegmenter::new_auto();
+}
+
+/// ใส่ ZWSP ระหว่างคำภาษาไทย (ตัดคำด้วย segmenter ของ ICU4X ซึ่งใช้ model LSTM กับภาษาไทย)
+///
+/// layout ของ `bevy_text` ตัดบรรทัดตาม UAX #14 ซึ่งไม่มีจุดตัดภายในข้อความไทยที่ไม่มีช่องว่าง
+/// ประโยคยาวจึงล้นกล่องข้อความ ข้อความภาษาอื่นไม่ถูกแก้
+pub fn insert_thai_breaks(text: &str) -> String {
+    if !text.chars().any(is_thai) {
+        return text.to_string();
+    }
+
+    let breaks: Vec<usize> = SEGMENTER.with(|segmenter| segmenter.segment_str(text).collect());
+
+    let mut result = String::with_capacity(text.len() + text.len() / 8);
+    let mut last = 0;
+    for offset in breaks {
+        if offset == 0 || offset >= text.len() {
+            continue;
+        }
+        result.push_str(&text[last..offset]);
+        let before = text[..offset].chars().next_back();
+        let after = text[offset..].chars().next();
+        if before.is_some_and(is_thai) && after.is_some_and(is_thai) {
+            result.push(ZWSP);
+        }
+        last = offset;
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+fn is_thai(c: char) -> bool {
+    ('\u{0E00}'..='\u{0E7F}').contains(&c)
+}
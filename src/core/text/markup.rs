@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use crate::core::language::types::LanguageCode;
+use crate::core::text::components::TextStylePreset;
+use crate::core::text::styles::TextStyleResource;
+
+/// ข้อความช่วงหนึ่งที่มีรูปแบบเดียวกัน (กลายเป็น `TextSection` หนึ่งอัน)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupSpan {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub color: Option<Color>,
+}
+
+impl MarkupSpan {
+    /// style ของ span โดยใช้ preset เป็นฐาน
+    pub fn style(&self, preset: &TextStylePreset, text_styles: &TextStyleResource, language: &LanguageCode) -> TextStyle {
+        let mut style = preset.to_style(text_styles, language);
+        // ไม่มี font ตัวหนาเอียง `[b][i]` จึงใช้ตัวเอียง
+        if self.italic {
+            style.font = text_styles.get_italic_font(language);
+        } else if self.bold {
+            style.font = text_styles.get_bold_font(language);
+        }
+        if let Some(color) = self.color {
+            style.color = color;
+        }
+        style
+    }
+}
+
+/// แยก markup `[b]`, `[i]` และ `[color=#f00]` (ปิดด้วย `[/b]`, `[/i]`, `[/color]`) เป็น span
+///
+/// tag ซ้อนกันได้ ส่วน tag ที่ไม่รู้จักหรือปิดโดยไม่ได้เปิดจะคงไว้เป็นข้อความ
+pub fn parse_markup(text: &str) -> Vec<MarkupSpan> {
+    let mut spans: Vec<MarkupSpan> = Vec::new();
+    let mut bold = 0usize;
+    let mut italic = 0usize;
+    let mut colors: Vec<Color> = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let (literal, consumed) = match rest.find('[') {
+            Some(0) => match rest.find(']') {
+                Some(end) if apply_tag(&rest[1..end], &mut bold, &mut italic, &mut colors) => {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+                // ไม่ใช่ tag: เก็บ `[` เป็นข้อความแล้วหาต่อ
+                _ => ("[", 1),
+            },
+            Some(start) => (&rest[..start], start),
+            None => (rest, rest.len()),
+        };
+
+        let color = colors.last().copied();
+        match spans.last_mut() {
+            Some(span) if span.bold == (bold > 0) && span.italic == (italic > 0) && span.color == color => {
+                span.text.push_str(literal);
+            }
+            _ => spans.push(MarkupSpan {
+                text: literal.to_string(),
+                bold: bold > 0,
+                italic: italic > 0,
+                color,
+            }),
+        }
+        rest = &rest[consumed..];
+    }
+
+    spans
+}
+
+/// ข้อความที่ตัด markup ออกแล้ว
+pub fn strip_markup(text: &str) -> String {
+    parse_markup(text).into_iter().map(|span| span.text).collect()
+}
+
+/// คืน `false` ถ้าไม่ใช่ tag ที่รู้จัก
+fn apply_tag(tag: &str, bold: &mut usize, italic: &mut usize, colors: &mut Vec<Color>) -> bool {
+    fn close(depth: &mut usize) -> bool {
+        let open = *depth > 0;
+        *depth = depth.saturating_sub(1);
+        open
+    }
+
+    match tag {
+        "b" => *bold += 1,
+        "i" => *italic += 1,
+        "/b" => return close(bold),
+        "/i" => return close(italic),
+        "/color" => return colors.pop().is_some(),
+        _ => {
+            let Some(color) = tag.strip_prefix("color=").and_then(|hex| Srgba::hex(hex.trim()).ok()) else {
+                return false;
+            };
+            colors.push(color.into());
+        }
+    }
+    true
+}
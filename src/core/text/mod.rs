@@ -1,6 +1,8 @@
 pub mod styles;
 pub mod components;
 pub mod builder;
+pub mod markup;
+pub mod linebreak;
 
 pub use styles::*;
 pub use components::*;
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy::asset::LoadState;
 use crate::core::language::types::LanguageCode;
 
 /// ระบบสร้าง TextStyle ที่รวม font + language + size + color
@@ -8,6 +9,9 @@ pub struct TextStyleResource {
     thai_bold: Handle<Font>,
     english_regular: Handle<Font>,
     english_bold: Handle<Font>,
+    /// ตัวเอียงของ `[i]` (font ภาษาญี่ปุ่นไม่มีตัวเอียง จึงใช้ font ปกติ)
+    thai_italic: Handle<Font>,
+    english_italic: Handle<Font>,
     japanese_regular: Handle<Font>,
     japanese_bold: Handle<Font>,
     initialized: bool,
@@ -20,6 +24,8 @@ impl Default for TextStyleResource {
             thai_bold: Handle::default(),
             english_regular: Handle::default(),
             english_bold: Handle::default(),
+            thai_italic: Handle::default(),
+            english_italic: Handle::default(),
             japanese_regular: Handle::default(),
             japanese_bold: Handle::default(),
             initialized: false,
@@ -34,6 +40,8 @@ impl TextStyleResource {
             thai_bold: asset_server.load("fonts/thai/NotoSansThai-Bold.ttf"),
             english_regular: asset_server.load("fonts/thai/NotoSansThai-Regular.ttf"),
             english_bold: asset_server.load("fonts/thai/NotoSansThai-Bold.ttf"),
+            thai_italic: asset_server.load("fonts/thai/Sarabun-Italic.ttf"),
+            english_italic: asset_server.load("fonts/thai/Sarabun-Italic.ttf"),
             japanese_regular: asset_server.load("fonts/japanese/NotoSansJP-Regular.ttf"),
             japanese_bold: asset_server.load("fonts/japanese/NotoSansJP-Bold.ttf"),
             initialized: true,
@@ -47,6 +55,20 @@ impl TextStyleResource {
         }
     }
 
+    /// font ตัวเอียงที่โหลดไม่ได้ (เช่น ไม่ได้ใส่ไฟล์ไว้) ถูกแทนด้วย font ปกติ ข้อความ `[i]` จะได้ไม่หายไปทั้งบรรทัด
+    pub fn fallback_missing_italic(&mut self, asset_server: &AssetServer) {
+        let fonts = [
+            (&mut self.thai_italic, &self.thai_regular),
+            (&mut self.english_italic, &self.english_regular),
+        ];
+        for (italic, regular) in fonts {
+            if *italic != *regular && matches!(asset_server.load_state(&*italic), LoadState::Failed(_)) {
+                warn!("Italic font {:?} failed to load, using the regular font", italic.path());
+                *italic = regular.clone();
+            }
+        }
+    }
+
     /// สร้าง TextStyle สำหรับ regular font
     pub fn regular(&self, language: &LanguageCode, size: f32, color: Color) -> TextStyle {
         TextStyle {
@@ -82,6 +104,15 @@ impl TextStyleResource {
             LanguageCode::Japanese => self.japanese_bold.clone(),
         }
     }
+
+    /// ดึง italic font ตามภาษา
+    pub fn get_italic_font(&self, language: &LanguageCode) -> Handle<Font> {
+        match language {
+            LanguageCode::Thai => self.thai_italic.clone(),
+            LanguageCode::English => self.english_italic.clone(),
+            LanguageCode::Japanese => self.japanese_regular.clone(),
+        }
+    }
 }
 
 // Common text colors
@@ -97,4 +128,5 @@ pub fn ensure_text_styles_initialized(
     asset_server: Res<AssetServer>,
 ) {
     text_styles.ensure_initialized(&asset_server);
+    text_styles.fallback_missing_italic(&asset_server);
 }
@@ -0,0 +1,359 @@
+//! ตรวจ dialog และ language asset โดยไม่ต้องเปิดเกม (ใช้โดย `helium-cli validate`)
+//!
+//! โหลดไฟล์ด้วย serde type / importer ตัวเดียวกับตอนเล่นจริง แล้วรายงานปัญหาทั้งหมดที่เจอ
+//! แทนการหยุดที่ error แรกแบบ asset loader
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::core::dialog::action::DialogAction;
+use crate::core::dialog::ink::import_ink;
+use crate::core::dialog::script::parse_script;
+use crate::core::dialog::yarn::import_yarn;
+use crate::core::language::types::{LanguageCode, LanguagePack};
+use crate::core::story::manifest::{StoryManifest, STORY_MANIFEST};
+use crate::types::{DialogScene, JumpTarget};
+
+const DIALOG_DIR: &str = "dialogs";
+const LANGUAGE_DIR: &str = "lang";
+const DIALOG_EXTENSIONS: &[&str] = &["dialog.json", "hel", "ink.json", "yarn"];
+
+/// ปัญหาหนึ่งรายการ พร้อมไฟล์และ index ของ entry (ถ้ามี)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub file: PathBuf,
+    pub entry: Option<usize>,
+    pub message: String,
+    /// คำเตือน: แสดงแต่ไม่ทำให้ `helium-cli validate` ล้มเหลว
+    pub warning: bool,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.warning { "warning: " } else { "" };
+        match self.entry {
+            Some(entry) => write!(f, "{}: entry {}: {}{}", self.file.display(), entry, severity, self.message),
+            None => write!(f, "{}: {}{}", self.file.display(), severity, self.message),
+        }
+    }
+}
+
+/// ตรวจ `<assets>/story.json`, ทุก dialog ใน `<assets>/dialogs` และทุก language pack ใน `<assets>/lang`
+///
+/// `custom_actions` คือชื่อ action ที่เกมลงทะเบียนผ่าน `VNPlugin::with_action`
+pub fn validate_assets(assets: &Path, custom_actions: &HashSet<String>) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let dialog_dir = assets.join(DIALOG_DIR);
+
+    let mut scenes = Vec::new();
+    for file in list_files(&dialog_dir, &mut problems) {
+        let Some(name) = file.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !DIALOG_EXTENSIONS.iter().any(|extension| name.ends_with(&format!(".{}", extension))) {
+            continue;
+        }
+        match load_scene(&file, custom_actions) {
+            Ok(scene) => scenes.push((file, scene)),
+            Err(e) => problems.push(Problem {
+                file,
+                entry: None,
+                message: format!("{:#}", e),
+                warning: false,
+            }),
+        }
+    }
+
+    // scene ที่ `change_scene` อ้างถึงได้คือ scene ที่ลงทะเบียนใน `story.json` เท่านั้น
+    let mut registered = HashMap::new();
+    if let Some(manifest) = load_manifest(assets, &mut problems) {
+        for info in &manifest.scenes {
+            let file = dialog_dir.join(&info.path);
+            match scenes.iter().find(|(scene_file, _)| *scene_file == file) {
+                Some((_, scene)) => {
+                    registered.insert(info.name.clone(), Some(scene));
+                }
+                None if file.exists() => {
+                    registered.insert(info.name.clone(), None);
+                }
+                None => problems.push(Problem {
+                    file: assets.join(STORY_MANIFEST),
+                    entry: None,
+                    message: format!("scene `{}` points to `{}` which does not exist", info.name, file.display()),
+                    warning: false,
+                }),
+            }
+        }
+    }
+
+    for (file, scene) in &scenes {
+        problems.extend(validate_scene(file, scene, &registered, custom_actions));
+    }
+
+    for file in list_files(&assets.join(LANGUAGE_DIR), &mut problems) {
+        if file.extension().is_some_and(|extension| extension == "json") {
+            problems.extend(validate_language_pack(&file));
+        }
+    }
+
+    problems
+}
+
+/// โหลด scene ตามนามสกุลไฟล์ (ยังไม่ resolve label เพื่อให้รายงานได้ครบทุกจุด)
+pub fn load_scene(path: &Path, custom_actions: &HashSet<String>) -> anyhow::Result<DialogScene> {
+    let bytes = std::fs::read(path)?;
+    let name = path.to_string_lossy();
+
+    if name.ends_with(".hel") {
+        Ok(parse_script(std::str::from_utf8(&bytes)?, custom_actions)?)
+    } else if name.ends_with(".yarn") {
+        Ok(import_yarn(std::str::from_utf8(&bytes)?, custom_actions)?)
+    } else if name.ends_with(".ink.json") {
+        import_ink(&bytes, custom_actions)
+    } else {
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// ตรวจ scene เดียว; `registered` คือ scene ที่ลงทะเบียนไว้ (`None` = ไฟล์โหลดไม่ผ่าน)
+pub fn validate_scene(
+    file: &Path,
+    scene: &DialogScene,
+    registered: &HashMap<String, Option<&DialogScene>>,
+    custom_actions: &HashSet<String>,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    // ทุกข้อความต้องมีครบทุกภาษาที่เกมรองรับ (ไม่งั้นจะแสดง "[No text in ...]" ตอนเล่น)
+    // ภาษาที่ไม่มีเลยทั้ง scene (เช่น .yarn / .ink ที่มีภาษาเดียว) เป็นแค่คำเตือนครั้งเดียวแทนทุก entry
+    let used: HashSet<&str> = scene
+        .entries
+        .iter()
+        .filter(|entry| !entry.is_control())
+        .flat_map(|entry| {
+            entry
+                .text
+                .keys()
+                .chain(entry.choices.iter().flat_map(|choice| choice.text.keys()))
+        })
+        .map(String::as_str)
+        .collect();
+    let mut languages = Vec::new();
+    for language in LanguageCode::ALL.iter().map(LanguageCode::dialog_key) {
+        if used.contains(language) {
+            languages.push(language);
+        } else if !used.is_empty() {
+            problems.push(Problem {
+                file: file.to_path_buf(),
+                entry: None,
+                message: format!("scene has no text in language `{}`", language),
+                warning: true,
+            });
+        }
+    }
+
+    let mut report = |entry: Option<usize>, message: String| {
+        problems.push(Problem {
+            file: file.to_path_buf(),
+            entry,
+            message,
+            warning: false,
+        });
+    };
+
+    if scene.entries.is_empty() {
+        report(None, "scene has no entries".to_string());
+    }
+
+    let mut labels = HashMap::new();
+    for (index, entry) in scene.entries.iter().enumerate() {
+        let Some(label) = &entry.label else {
+            continue;
+        };
+        if let Some(previous) = labels.insert(label.as_str(), index) {
+            report(Some(index), format!("label `{}` is already used by entry {}", label, previous));
+        }
+    }
+
+    let characters: HashSet<&str> = scene.characters.iter().map(|c| c.name.as_str()).collect();
+
+    let check_target = |target: &JumpTarget, what: &str| -> Option<String> {
+        match target {
+            JumpTarget::Stage(stage) if *stage >= scene.entries.len() => Some(format!(
+                "{} targets stage {} but the scene has {} entries",
+                what,
+                stage,
+                scene.entries.len()
+            )),
+            JumpTarget::Label(label) if !labels.contains_key(label.as_str()) => {
+                Some(format!("{} targets unknown label `{}`", what, label))
+            }
+            JumpTarget::Scene { scene: name, label } => check_scene(registered, name, label.as_deref())
+                .map(|problem| format!("{} {}", what, problem)),
+            _ => None,
+        }
+    };
+
+    for (index, entry) in scene.entries.iter().enumerate() {
+        if entry.is_control() {
+            if !entry.character.is_empty() && !characters.contains(entry.character.as_str()) {
+                report(Some(index), format!("character `{}` is not in `characters`", entry.character));
+            }
+        } else {
+            if !characters.contains(entry.character.as_str()) {
+                report(Some(index), format!("character `{}` is not in `characters`", entry.character));
+            }
+            for language in &languages {
+                if !entry.text.contains_key(*language) {
+                    report(Some(index), format!("text is missing language `{}`", language));
+                }
+            }
+        }
+
+        for state in &entry.character_states {
+            if !characters.contains(state.name.as_str()) {
+                report(Some(index), format!("character_states references unknown character `{}`", state.name));
+            }
+        }
+
+        if let Some(problem) = entry.auto_proceed.as_ref().and_then(|target| check_target(target, "auto_proceed")) {
+            report(Some(index), problem);
+        }
+
+        for (choice_index, choice) in entry.choices.iter().enumerate() {
+            let what = format!("choice {}", choice_index);
+            if let Some(problem) = check_target(&choice.target, &what) {
+                report(Some(index), problem);
+            }
+            for language in &languages {
+                if !choice.text.contains_key(*language) {
+                    report(Some(index), format!("{} text is missing language `{}`", what, language));
+                }
+            }
+        }
+
+        for action in &entry.actions {
+            match action {
+                DialogAction::Jump { target, .. } => {
+                    if let Some(problem) = check_target(target, &format!("action `{}`", action)) {
+                        report(Some(index), problem);
+                    }
+                }
+                DialogAction::ChangeScene { scene: name, label } | DialogAction::CallScene { scene: name, label } => {
+                    if let Some(problem) = check_scene(registered, name, label.as_deref()) {
+                        report(Some(index), format!("action `{}` {}", action, problem));
+                    }
+                }
+                DialogAction::Custom { name, .. } if !custom_actions.contains(name) => {
+                    report(Some(index), format!("unknown action `{}`", action));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    problems
+}
+
+/// ตรวจว่า scene ปลายทางลงทะเบียนไว้ และมี label ที่อ้างถึง
+fn check_scene(
+    registered: &HashMap<String, Option<&DialogScene>>,
+    name: &str,
+    label: Option<&str>,
+) -> Option<String> {
+    match (registered.get(name), label) {
+        (None, _) => Some(format!("targets scene `{}` which is not registered in `story.json`", name)),
+        (Some(Some(target)), Some(label)) if target.label_index(label).is_none() => {
+            Some(format!("targets unknown label `{}` in scene `{}`", label, name))
+        }
+        _ => None,
+    }
+}
+
+fn load_manifest(assets: &Path, problems: &mut Vec<Problem>) -> Option<StoryManifest> {
+    let file = assets.join(STORY_MANIFEST);
+    let manifest = std::fs::read(&file)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| Ok(serde_json::from_slice::<StoryManifest>(&bytes)?));
+    let mut problem = |message: String| {
+        problems.push(Problem {
+            file: file.clone(),
+            entry: None,
+            message,
+            warning: false,
+        });
+    };
+
+    match manifest {
+        Ok(manifest) => {
+            if let Err(e) = manifest.check() {
+                problem(e.to_string());
+            }
+            Some(manifest)
+        }
+        Err(e) => {
+            problem(e.to_string());
+            None
+        }
+    }
+}
+
+/// language pack ต้อง parse ได้ครบทุก field และไม่มีข้อความว่าง
+pub fn validate_language_pack(file: &Path) -> Vec<Problem> {
+    let problem = |message: String| Problem {
+        file: file.to_path_buf(),
+        entry: None,
+        message,
+        warning: false,
+    };
+
+    let bytes = match std::fs::read(file) {
+        Ok(bytes) => bytes,
+        Err(e) => return vec![problem(e.to_string())],
+    };
+    let pack: LanguagePack = match serde_json::from_slice(&bytes) {
+        Ok(pack) => pack,
+        Err(e) => return vec![problem(e.to_string())],
+    };
+
+    let mut problems = Vec::new();
+    let sections = [
+        ("ui", serde_json::to_value(&pack.ui)),
+        ("dialog", serde_json::to_value(&pack.dialog)),
+        ("game", serde_json::to_value(&pack.game)),
+    ];
+    for (section, value) in sections {
+        let Ok(serde_json::Value::Object(fields)) = value else {
+            continue;
+        };
+        for (key, text) in fields {
+            if text.as_str().is_some_and(|text| text.trim().is_empty()) {
+                problems.push(problem(format!("`{}.{}` is empty", section, key)));
+            }
+        }
+    }
+    problems
+}
+
+fn list_files(dir: &Path, problems: &mut Vec<Problem>) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            problems.push(Problem {
+                file: dir.to_path_buf(),
+                entry: None,
+                message: e.to_string(),
+                warning: false,
+            });
+            return Vec::new();
+        }
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
@@ -1,19 +1,34 @@
 use bevy::prelude::*;
+use bevy::window::WindowMode;
 use helium::core::plugin::VNPlugin;
 use helium::core::game_state::GameState;
+use helium::core::resources::SettingsResource;
 
 fn main() {
+    // อ่าน settings ก่อนสร้างหน้าต่างเพื่อเปิดด้วยขนาดและโหมดที่บันทึกไว้
+    let settings = SettingsResource::load().unwrap_or_else(|err| {
+        eprintln!("Using default settings: {:#}", err);
+        SettingsResource::default()
+    });
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Helium Visual Novel".to_string(),
-                resolution: (1280.0, 720.0).into(),
+                resolution: settings.resolution.into(),
+                mode,
                 resizable: true,
                 ..default()
             }),
             ..default()
         }))
-        .add_plugins(VNPlugin)
+        .insert_resource(settings)
+        .add_plugins(VNPlugin::default())
         .insert_state(GameState::MainMenu)
         .run();
 }
\ No newline at end of file
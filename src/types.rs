@@ -2,9 +2,14 @@ use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::asset::{AssetLoader, LoadContext, AsyncReadExt};
 use bevy::utils::ConditionalSendFuture;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
+use crate::core::dialog::action::{ActionRegistry, DialogAction};
+use crate::core::dialog::script::parse_script;
+use crate::core::dialog::ink::import_ink;
+use crate::core::dialog::yarn::import_yarn;
+use crate::core::story::condition::Condition;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DialogCharacter {
@@ -16,12 +21,74 @@ pub struct DialogCharacter {
     pub positions: HashMap<String, Vec2>,
 }
 
+/// เป้าหมายของการกระโดด: index ของ entry, label ใน scene เดียวกัน
+/// หรือข้าม scene แบบ `school#lunch` (`school#` = ต้น scene)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JumpTarget {
+    Stage(usize),
+    Label(String),
+    Scene { scene: String, label: Option<String> },
+}
+
+impl JumpTarget {
+    pub fn parse(source: &str) -> Self {
+        match source.split_once('#') {
+            Some((scene, label)) => JumpTarget::Scene {
+                scene: scene.trim().to_string(),
+                label: Some(label.trim()).filter(|l| !l.is_empty()).map(str::to_string),
+            },
+            None => JumpTarget::Label(source.trim().to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for JumpTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JumpTarget::Stage(stage) => write!(f, "{}", stage),
+            JumpTarget::Label(label) => write!(f, "{}", label),
+            JumpTarget::Scene { scene, label } => {
+                write!(f, "{}#{}", scene, label.as_deref().unwrap_or(""))
+            }
+        }
+    }
+}
+
+impl Serialize for JumpTarget {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JumpTarget::Stage(stage) => serializer.serialize_u64(*stage as u64),
+            other => serializer.collect_str(other),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JumpTarget {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawTarget {
+            Stage(usize),
+            Named(String),
+        }
+
+        Ok(match RawTarget::deserialize(deserializer)? {
+            RawTarget::Stage(stage) => JumpTarget::Stage(stage),
+            RawTarget::Named(name) => JumpTarget::parse(&name),
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DialogChoice {
     pub text: HashMap<String, String>,
-    pub target_stage: usize,
+    #[serde(alias = "target_stage")]
+    pub target: JumpTarget,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// แสดงเป็นปุ่มที่กดไม่ได้แทนการซ่อน เมื่อเงื่อนไขไม่ผ่าน
     #[serde(default)]
-    pub conditions: Vec<String>,
+    pub show_locked: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -32,20 +99,56 @@ pub struct CharacterState {
     pub highlight: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionType {
+    FadeIn,
+    FadeOut,
+    #[serde(alias = "dissolve")]
+    Crossfade,
+    #[serde(alias = "wipe")]
+    WipeLeft,
+    WipeRight,
+    WipeUp,
+    WipeDown,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DialogTransition {
+    pub type_name: TransitionType,
+    #[serde(default = "default_transition_duration")]
+    pub duration: f32,
+}
+
+fn default_transition_duration() -> f32 {
+    0.5
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DialogEntry {
+    #[serde(default)]
+    pub label: Option<String>,
     pub character: String,
     pub text: HashMap<String, String>,
     #[serde(default)]
-    pub actions: Vec<String>,
+    pub actions: Vec<DialogAction>,
     #[serde(default)]
     pub choices: Vec<DialogChoice>,
     #[serde(default)]
-    pub auto_proceed: Option<usize>,
+    pub auto_proceed: Option<JumpTarget>,
     #[serde(default)]
     pub character_states: Vec<CharacterState>,
     #[serde(default)]
     pub background: Option<String>,
+    #[serde(default)]
+    pub transition: Option<DialogTransition>,
+}
+
+impl DialogEntry {
+    /// entry ที่ไม่มีข้อความและตัวเลือก ใช้รัน action / กระโดดต่อทันทีโดยไม่รอผู้เล่น
+    pub fn is_control(&self) -> bool {
+        self.choices.is_empty() && self.text.values().all(|text| text.is_empty())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, TypePath, Asset, Clone)]
@@ -56,8 +159,118 @@ pub struct DialogScene {
     pub default_background: String,
 }
 
-#[derive(Default)]
-pub struct DialogLoader;
+impl DialogScene {
+    pub fn label_index(&self, label: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.label.as_deref() == Some(label))
+    }
+
+    /// แปลง label ภายใน scene เป็น index และตรวจว่า index ทุกตัวอยู่ในช่วง
+    /// (label ข้าม scene ตรวจโดย `check_scene_links` เมื่อ scene ที่ลงทะเบียนโหลดครบ)
+    pub fn resolve_labels(&mut self) -> anyhow::Result<()> {
+        let mut labels = HashMap::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let Some(label) = &entry.label else {
+                continue;
+            };
+            if let Some(previous) = labels.insert(label.clone(), index) {
+                anyhow::bail!("entry {}: label `{}` is already used by entry {}", index, label, previous);
+            }
+        }
+
+        let entry_count = self.entries.len();
+        let resolve = |target: &mut JumpTarget, index: usize| -> anyhow::Result<()> {
+            match target {
+                JumpTarget::Label(label) => match labels.get(label.as_str()) {
+                    Some(stage) => *target = JumpTarget::Stage(*stage),
+                    None => anyhow::bail!("entry {}: unknown label `{}`", index, label),
+                },
+                JumpTarget::Stage(stage) if *stage >= entry_count => {
+                    anyhow::bail!("entry {}: target stage {} is out of range ({} entries)", index, stage, entry_count);
+                }
+                _ => {}
+            }
+            Ok(())
+        };
+
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            if let Some(target) = entry.auto_proceed.as_mut() {
+                resolve(target, index)?;
+            }
+            for choice in entry.choices.iter_mut() {
+                resolve(&mut choice.target, index)?;
+            }
+            for action in entry.actions.iter_mut() {
+                if let DialogAction::Jump { target, .. } = action {
+                    resolve(target, index)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// ทุกจุดที่อ้างข้าม scene เป็น `(entry, scene, label)` ทั้ง `scene#label`, `change_scene` และ `call_scene`
+    pub fn scene_links(&self) -> Vec<(usize, &str, Option<&str>)> {
+        let mut links = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let targets = entry
+                .auto_proceed
+                .iter()
+                .chain(entry.choices.iter().map(|choice| &choice.target))
+                .chain(entry.actions.iter().filter_map(|action| match action {
+                    DialogAction::Jump { target, .. } => Some(target),
+                    _ => None,
+                }));
+            for target in targets {
+                if let JumpTarget::Scene { scene, label } = target {
+                    links.push((index, scene.as_str(), label.as_deref()));
+                }
+            }
+            for action in &entry.actions {
+                if let DialogAction::ChangeScene { scene, label } | DialogAction::CallScene { scene, label } = action {
+                    links.push((index, scene.as_str(), label.as_deref()));
+                }
+            }
+        }
+        links
+    }
+
+    /// ตรวจว่า custom action ทุกตัวมี handler ลงทะเบียนไว้
+    pub fn check_actions(&self, custom_actions: &HashSet<String>) -> anyhow::Result<()> {
+        for (index, entry) in self.entries.iter().enumerate() {
+            for action in &entry.actions {
+                match action {
+                    DialogAction::Custom { name, .. } if !custom_actions.contains(name) => {
+                        anyhow::bail!("entry {}: unknown action `{}`", index, action);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct DialogLoader {
+    custom_actions: HashSet<String>,
+}
+
+/// ชื่อ custom action ที่ลงทะเบียนไว้ ใช้ตรวจ dialog ตอนโหลด
+fn registered_actions(world: &World) -> HashSet<String> {
+    world
+        .get_resource::<ActionRegistry>()
+        .map(ActionRegistry::names)
+        .unwrap_or_default()
+}
+
+impl FromWorld for DialogLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            custom_actions: registered_actions(world),
+        }
+    }
+}
 
 impl AssetLoader for DialogLoader {
     type Asset = DialogScene;
@@ -73,7 +286,9 @@ impl AssetLoader for DialogLoader {
         Box::pin(async move {
             let mut bytes = Vec::new();
             reader.read_to_end(&mut bytes).await?;
-            let dialog_scene: DialogScene = serde_json::from_slice(&bytes)?;
+            let mut dialog_scene: DialogScene = serde_json::from_slice(&bytes)?;
+            dialog_scene.resolve_labels()?;
+            dialog_scene.check_actions(&self.custom_actions)?;
             Ok(dialog_scene)
         })
     }
@@ -81,4 +296,123 @@ impl AssetLoader for DialogLoader {
     fn extensions(&self) -> &[&str] {
         &["dialog.json"]
     }
-}
\ No newline at end of file
+}
+/// โหลด `.hel` screenplay script เป็น `DialogScene` แบบเดียวกับ `DialogLoader`
+pub struct ScriptLoader {
+    custom_actions: HashSet<String>,
+}
+
+impl FromWorld for ScriptLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            custom_actions: registered_actions(world),
+        }
+    }
+}
+
+impl AssetLoader for ScriptLoader {
+    type Asset = DialogScene;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture + Future<Output = Result<<Self as AssetLoader>::Asset, <Self as AssetLoader>::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let path = load_context.path().display().to_string();
+            let mut dialog_scene = parse_script(&source, &self.custom_actions)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+            dialog_scene.resolve_labels()?;
+            Ok(dialog_scene)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["hel"]
+    }
+}
+
+/// โหลด Ink ที่ compile แล้ว (`.ink.json`) เป็น `DialogScene`
+pub struct InkLoader {
+    custom_actions: HashSet<String>,
+}
+
+impl FromWorld for InkLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            custom_actions: registered_actions(world),
+        }
+    }
+}
+
+impl AssetLoader for InkLoader {
+    type Asset = DialogScene;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture + Future<Output = Result<<Self as AssetLoader>::Asset, <Self as AssetLoader>::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let path = load_context.path().display().to_string();
+            let mut dialog_scene = import_ink(&bytes, &self.custom_actions)
+                .map_err(|e| anyhow::anyhow!("{}: {:#}", path, e))?;
+            dialog_scene.resolve_labels()?;
+            Ok(dialog_scene)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ink.json"]
+    }
+}
+
+/// โหลด Yarn Spinner (`.yarn`) เป็น `DialogScene`
+pub struct YarnLoader {
+    custom_actions: HashSet<String>,
+}
+
+impl FromWorld for YarnLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            custom_actions: registered_actions(world),
+        }
+    }
+}
+
+impl AssetLoader for YarnLoader {
+    type Asset = DialogScene;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut bevy::asset::io::Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture + Future<Output = Result<<Self as AssetLoader>::Asset, <Self as AssetLoader>::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let path = load_context.path().display().to_string();
+            let mut dialog_scene = import_yarn(&source, &self.custom_actions)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+            dialog_scene.resolve_labels()?;
+            Ok(dialog_scene)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["yarn"]
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use crate::core::dialog::backlog::Backlog;
+use crate::core::dialog::rollback::RollbackEvent;
+use crate::core::game_state::{GameState, ChangeStateEvent};
+use crate::core::input::action::{ActionState, InputAction};
+use crate::core::language::manager::LanguageResource;
+use crate::core::language::types::LanguagePack;
+use crate::core::resources::{DialogHistory, DialogResource, VNState};
+use crate::core::text::builder::TextBuilder;
+use crate::core::text::components::TextStylePreset;
+use crate::core::text::linebreak::insert_thai_breaks;
+use crate::core::text::styles::TextStyleResource;
+use crate::types::DialogScene;
+
+#[derive(Component)]
+pub struct BacklogUI;
+
+/// รายการใน backlog เลื่อนด้วย `bottom` (0 = บรรทัดล่าสุดอยู่ล่างสุด)
+#[derive(Component, Default)]
+pub struct BacklogList {
+    offset: f32,
+}
+
+/// บรรทัดที่คลิกเพื่อย้อนกลับไปได้
+#[derive(Component)]
+pub struct BacklogLine {
+    pub scene: String,
+    pub stage: usize,
+}
+
+const BACKLOG_Z_LAYER: f32 = 20.0;
+/// ระยะเลื่อนต่อหนึ่งขีดของล้อเมาส์ / ปุ่มลูกศร
+const SCROLL_LINE_HEIGHT: f32 = 40.0;
+const BACKLOG_OVERLAY_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+const BACKLOG_PANEL_COLOR: Color = Color::srgba(0.1, 0.1, 0.15, 0.95);
+const BACKLOG_LINE_HOVER: Color = Color::srgba(0.3, 0.3, 0.4, 0.6);
+const BACKLOG_SPEAKER_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const BACKLOG_CHOICE_COLOR: Color = Color::srgb(0.6, 0.8, 1.0);
+
+pub fn setup_backlog_ui(
+    mut commands: Commands,
+    backlog: Res<Backlog>,
+    history: Res<DialogHistory>,
+    state: Res<VNState>,
+    dialog_resource: Res<DialogResource>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    text_styles: Res<TextStyleResource>,
+) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BACKLOG_OVERLAY_COLOR.into(),
+            z_index: ZIndex::Global(BACKLOG_Z_LAYER as i32),
+            ..default()
+        },
+        BacklogUI,
+        Name::new("backlog_overlay"),
+    )).with_children(|overlay| {
+        overlay.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(80.0),
+                    height: Val::Percent(85.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BACKLOG_PANEL_COLOR.into(),
+                border_radius: BorderRadius::all(Val::Px(15.0)),
+                ..default()
+            },
+            Name::new("backlog_panel"),
+        )).with_children(|panel| {
+            TextBuilder::localized_child(
+                panel,
+                "ui.backlog_title",
+                TextStylePreset::Title,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+
+            // Viewport ตัดส่วนที่ล้นออก รายการวางชิดล่างเพื่อให้เห็นบรรทัดล่าสุดก่อน
+            panel.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_grow: 1.0,
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Name::new("backlog_viewport"),
+            )).with_children(|viewport| {
+                viewport.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            position_type: PositionType::Absolute,
+                            bottom: Val::Px(0.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(12.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BacklogList::default(),
+                    Name::new("backlog_list"),
+                )).with_children(|list| {
+                    for entry in backlog.entries() {
+                        let Some(scene) = dialog_resource
+                            .scenes
+                            .get(&entry.scene)
+                            .and_then(|handle| dialog_scenes.get(handle))
+                        else {
+                            continue;
+                        };
+                        let Some((speaker, text)) = entry.render(scene, &state.language) else {
+                            continue;
+                        };
+
+                        if entry.choice.is_some() {
+                            TextBuilder::static_child(
+                                list,
+                                &insert_thai_breaks(&format!("» {}", text)),
+                                TextStylePreset::Custom(18.0, false, BACKLOG_CHOICE_COLOR),
+                                &language_resource,
+                                &text_styles,
+                            );
+                            continue;
+                        }
+
+                        let mut line = list.spawn(ButtonBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                                row_gap: Val::Px(2.0),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            border_radius: BorderRadius::all(Val::Px(6.0)),
+                            ..default()
+                        });
+                        if history.can_go_back_to(&entry.scene, entry.stage) {
+                            line.insert(BacklogLine {
+                                scene: entry.scene.clone(),
+                                stage: entry.stage,
+                            });
+                        }
+                        line.with_children(|line| {
+                            if let Some(speaker) = &speaker {
+                                TextBuilder::static_child(
+                                    line,
+                                    speaker,
+                                    TextStylePreset::Custom(18.0, true, BACKLOG_SPEAKER_COLOR),
+                                    &language_resource,
+                                    &text_styles,
+                                );
+                            }
+                            TextBuilder::static_child(
+                                line,
+                                &insert_thai_breaks(&text),
+                                TextStylePreset::Custom(18.0, false, Color::WHITE),
+                                &language_resource,
+                                &text_styles,
+                            );
+                        });
+                    }
+                });
+            });
+
+            TextBuilder::localized_child(
+                panel,
+                "ui.backlog_hint",
+                TextStylePreset::Hint,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+        });
+    });
+}
+
+/// `InputAction::Backlog` (H) เปิด/ปิด backlog ระหว่างเล่น (`Back` ปิดผ่าน `handle_pause_input`)
+pub fn handle_backlog_input(
+    actions: Res<ActionState>,
+    current_state: Res<State<GameState>>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    if !actions.just_pressed(InputAction::Backlog) {
+        return;
+    }
+    let new_state = match current_state.get() {
+        GameState::InGame => GameState::Backlog,
+        GameState::Backlog => GameState::InGame,
+        _ => return,
+    };
+    change_events.send(ChangeStateEvent { new_state });
+}
+
+/// เลื่อนด้วยล้อเมาส์หรือ `NavigateUp`/`NavigateDown` (ลูกศร / D-pad) ขึ้น = บรรทัดเก่ากว่า
+pub fn scroll_backlog(
+    mut mouse_wheel: EventReader<MouseWheel>,
+    actions: Res<ActionState>,
+    mut list_query: Query<(&mut BacklogList, &mut Style, &Node, &Parent)>,
+    node_query: Query<&Node>,
+) {
+    let mut delta: f32 = mouse_wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if actions.just_pressed(InputAction::NavigateUp) {
+        delta += SCROLL_LINE_HEIGHT;
+    }
+    if actions.just_pressed(InputAction::NavigateDown) {
+        delta -= SCROLL_LINE_HEIGHT;
+    }
+    if delta == 0.0 {
+        return;
+    }
+
+    for (mut list, mut style, node, parent) in list_query.iter_mut() {
+        let viewport_height = node_query.get(parent.get()).map_or(0.0, |viewport| viewport.size().y);
+        let max_offset = (node.size().y - viewport_height).max(0.0);
+        list.offset = (list.offset + delta).clamp(0.0, max_offset);
+        style.bottom = Val::Px(-list.offset);
+    }
+}
+
+pub fn handle_backlog_line_hover(
+    mut line_query: Query<(&Interaction, &mut BackgroundColor, &BacklogLine), Changed<Interaction>>,
+) {
+    for (interaction, mut bg_color, _) in line_query.iter_mut() {
+        *bg_color = match *interaction {
+            Interaction::Pressed | Interaction::Hovered => BACKLOG_LINE_HOVER.into(),
+            Interaction::None => Color::NONE.into(),
+        };
+    }
+}
+
+/// คลิกบรรทัดเพื่อย้อนกลับไปยังจุดนั้น
+pub fn handle_backlog_line_click(
+    line_query: Query<(&Interaction, &BacklogLine), Changed<Interaction>>,
+    mut rollback_events: EventWriter<RollbackEvent>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    for (interaction, line) in line_query.iter() {
+        if *interaction == Interaction::Pressed {
+            rollback_events.send(RollbackEvent {
+                scene: line.scene.clone(),
+                stage: line.stage,
+            });
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::InGame,
+            });
+            return;
+        }
+    }
+}
+
+pub fn cleanup_backlog_ui(
+    mut commands: Commands,
+    backlog_query: Query<Entity, With<BacklogUI>>,
+) {
+    for entity in backlog_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,13 +1,20 @@
-use crate::core::dialog::choice::{ChoiceButton, ChoiceState};
+use crate::core::dialog::choice::{ChoiceButton, ChoiceState, PresentedChoice};
+use crate::core::dialog::interpolate::interpolate;
 use crate::core::dialog::typewriter::TypewriterText;
+use crate::core::game_state::GameState;
 use crate::core::language::manager::LanguageResource;
 use crate::core::language::types::LanguagePack;
 use crate::core::resources::VNState;
+use crate::core::story::condition::evaluate_all;
+use crate::core::story::variables::StoryVariables;
 use crate::core::text::builder::TextBuilder;
+use crate::core::text::linebreak::insert_thai_breaks;
+use crate::core::text::markup::strip_markup;
 use crate::core::text::components::TextStylePreset;
 use crate::core::text::styles::TextStyleResource;
-use crate::types::{DialogChoice, DialogScene};
+use crate::types::DialogScene;
 use crate::ui::dialog::DialogText;
+use crate::ui::focus::Focusable;
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -26,10 +33,13 @@ const CHOICE_BUTTON_BG: Color = Color::srgba(0.2, 0.2, 0.25, 0.9);
 const CHOICE_BUTTON_BORDER: Color = Color::srgba(0.6, 0.6, 0.7, 0.5);
 const CHOICE_BUTTON_HOVER: Color = Color::srgba(0.3, 0.3, 0.4, 0.95);
 const CHOICE_BUTTON_ACTIVE: Color = Color::srgba(0.4, 0.4, 0.5, 1.0);
+const CHOICE_BUTTON_LOCKED: Color = Color::srgba(0.15, 0.15, 0.18, 0.7);
+const CHOICE_TEXT_LOCKED: Color = Color::srgba(0.6, 0.6, 0.65, 0.8);
 
 pub fn manage_choice_display(
     mut commands: Commands,
-    state: Res<VNState>,
+    mut state: ResMut<VNState>,
+    variables: Res<StoryVariables>,
     dialog_scenes: Res<Assets<DialogScene>>,
     language_resource: Res<LanguageResource>,
     language_packs: Res<Assets<LanguagePack>>,
@@ -39,11 +49,14 @@ pub fn manage_choice_display(
     existing_overlays: Query<Entity, With<ChoiceOverlay>>,
     typewriter_query: Query<&TypewriterText, With<DialogText>>,
 ) {
-    let should_show_choices = should_display_choices(&state, &dialog_scenes, &typewriter_query);
+    let should_show_choices = should_display_choices(&state, &variables, &dialog_scenes, &typewriter_query);
 
     if should_show_choices && !choice_state.active {
-        if let Some(choices) = get_current_choices(&state, &dialog_scenes) {
+        if let Some(mut choices) = get_current_choices(&state, &variables, &dialog_scenes) {
+            resolve_choice_text(&mut choices, &state, &variables, &dialog_scenes);
             cleanup_existing_choices(&mut commands, &existing_containers, &existing_overlays);
+            // auto-save ที่จุดเลือก ก่อนผู้เล่นตัดสินใจ
+            state.autosave_pending = true;
             choice_state.activate(choices.clone());
             create_choice_ui(
                 &mut commands,
@@ -62,36 +75,46 @@ pub fn manage_choice_display(
 
 fn should_display_choices(
     state: &VNState,
+    variables: &StoryVariables,
     dialog_scenes: &Assets<DialogScene>,
     typewriter_query: &Query<&TypewriterText, With<DialogText>>,
 ) -> bool {
-    if let Some(scene_handle) = &state.current_scene_handle {
-        if let Some(scene) = dialog_scenes.get(scene_handle) {
-            if state.stage < scene.entries.len() {
-                let entry = &scene.entries[state.stage];
-
-                if !entry.choices.is_empty() {
-                    if let Ok(typewriter) = typewriter_query.get_single() {
-                        return typewriter.char_index >= typewriter.full_text.chars().count();
-                    }
-                    return true;
-                }
-            }
+    if get_current_choices(state, variables, dialog_scenes).is_some() {
+        if let Ok(typewriter) = typewriter_query.get_single() {
+            return typewriter.is_finished();
         }
+        return true;
     }
     false
 }
 
+/// ตัวเลือกของ entry ปัจจุบัน โดยซ่อนตัวเลือกที่ conditions ไม่ผ่าน
+/// (หรือแสดงแบบกดไม่ได้ถ้า `show_locked`)
 fn get_current_choices(
     state: &VNState,
+    variables: &StoryVariables,
     dialog_scenes: &Assets<DialogScene>,
-) -> Option<Vec<DialogChoice>> {
+) -> Option<Vec<PresentedChoice>> {
     if let Some(scene_handle) = &state.current_scene_handle {
         if let Some(scene) = dialog_scenes.get(scene_handle) {
             if state.stage < scene.entries.len() {
                 let entry = &scene.entries[state.stage];
-                if !entry.choices.is_empty() {
-                    return Some(entry.choices.clone());
+                let choices: Vec<PresentedChoice> = entry
+                    .choices
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, choice)| {
+                        let available = evaluate_all(&choice.conditions, variables);
+                        (available || choice.show_locked).then(|| PresentedChoice {
+                            index,
+                            choice: choice.clone(),
+                            available,
+                        })
+                    })
+                    .collect();
+
+                if !choices.is_empty() {
+                    return Some(choices);
                 }
             }
         }
@@ -99,6 +122,27 @@ fn get_current_choices(
     None
 }
 
+/// แทนค่า `{var:...}` / `{char:...}` ในข้อความตัวเลือกของภาษาปัจจุบัน (ปุ่มตัวเลือกไม่แสดง markup)
+fn resolve_choice_text(
+    choices: &mut [PresentedChoice],
+    state: &VNState,
+    variables: &StoryVariables,
+    dialog_scenes: &Assets<DialogScene>,
+) {
+    let characters = state
+        .current_scene_handle
+        .as_ref()
+        .and_then(|handle| dialog_scenes.get(handle))
+        .map(|scene| scene.characters.as_slice())
+        .unwrap_or_default();
+
+    for presented in choices {
+        if let Some(text) = presented.choice.text.get_mut(&state.language) {
+            *text = insert_thai_breaks(&strip_markup(&interpolate(text, variables, characters, &state.language)));
+        }
+    }
+}
+
 fn cleanup_existing_choices(
     commands: &mut Commands,
     containers: &Query<Entity, With<ChoiceContainer>>,
@@ -118,7 +162,7 @@ fn create_choice_ui(
     language_resource: &LanguageResource,
     language_packs: &Assets<LanguagePack>,
     text_styles: &TextStyleResource,
-    choices: &[DialogChoice],
+    choices: &[PresentedChoice],
 ) {
     // Overlay
     commands.spawn((
@@ -182,8 +226,8 @@ fn create_choice_ui(
         });
 
         // Choice buttons
-        for (i, choice) in choices.iter().enumerate() {
-            create_choice_button(parent, state, i, choice, language_resource, text_styles);
+        for (i, presented) in choices.iter().enumerate() {
+            create_choice_button(parent, state, i, presented, language_resource, text_styles);
         }
     });
 }
@@ -192,10 +236,11 @@ fn create_choice_button(
     parent: &mut ChildBuilder,
     state: &VNState,
     index: usize,
-    choice: &DialogChoice,
+    presented: &PresentedChoice,
     language_resource: &LanguageResource,
     text_styles: &TextStyleResource,
 ) {
+    let choice = &presented.choice;
     let current_lang = match state.language.as_str() {
         "thai" => "thai",
         "english" => "english",
@@ -207,7 +252,7 @@ fn create_choice_button(
         .cloned()
         .unwrap_or_else(|| format!("[No choice text in {}]", current_lang));
 
-    parent.spawn((
+    let mut button = parent.spawn((
         ButtonBundle {
             style: Style {
                 width: Val::Percent(100.0),
@@ -219,17 +264,23 @@ fn create_choice_button(
                 padding: UiRect::all(Val::Px(12.0)),
                 ..default()
             },
-            background_color: CHOICE_BUTTON_BG.into(),
+            background_color: if presented.available { CHOICE_BUTTON_BG } else { CHOICE_BUTTON_LOCKED }.into(),
             border_color: CHOICE_BUTTON_BORDER.into(),
             border_radius: BorderRadius::all(Val::Px(12.0)),
             ..default()
         },
         ChoiceButton {
-            choice_index: index,
-            target_stage: choice.target_stage,
+            choice_index: presented.index,
+            target: choice.target.clone(),
+            available: presented.available,
         },
         Name::new(format!("choice_button_{}", index)),
-    )).with_children(|button| {
+    ));
+    // ตัวเลือกที่ล็อกอยู่ข้ามไปตอนย้าย focus
+    if presented.available {
+        button.insert(Focusable::new(GameState::InGame, CHOICE_BUTTON_BG, CHOICE_BUTTON_HOVER));
+    }
+    button.with_children(|button| {
         // Number circle
         button.spawn(NodeBundle {
             style: Style {
@@ -257,7 +308,7 @@ fn create_choice_button(
         TextBuilder::static_child_with_components(
             button,
             &choice_text,
-            TextStylePreset::Custom(26.0, false, Color::WHITE),
+            TextStylePreset::Custom(26.0, false, if presented.available { Color::WHITE } else { CHOICE_TEXT_LOCKED }),
             language_resource,
             text_styles,
             (
@@ -269,9 +320,13 @@ fn create_choice_button(
 }
 
 pub fn highlight_choice_button(
-    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<ChoiceButton>)>,
+    mut query: Query<(&Interaction, &ChoiceButton, &mut BackgroundColor), Changed<Interaction>>,
 ) {
-    for (interaction, mut bg_color) in query.iter_mut() {
+    for (interaction, button, mut bg_color) in query.iter_mut() {
+        if !button.available {
+            continue;
+        }
+
         match *interaction {
             Interaction::Pressed => {
                 *bg_color = CHOICE_BUTTON_ACTIVE.into();
@@ -1,15 +1,23 @@
 use bevy::prelude::*;
-use crate::core::resources::{DialogHistory, DialogResource, VNState};
+use crate::core::resources::{DialogResource, SettingsResource, VNState};
 use crate::core::dialog::choice::ChoiceState;
+use crate::core::dialog::auto::AutoState;
+use crate::core::dialog::skip::SkipState;
+use crate::core::dialog::action::{ActionWait, DialogAction, DialogActionEvent};
+use crate::core::story::variables::StoryVariables;
+use crate::core::scene::transition::TransitionState;
 use crate::core::dialog::typewriter::TypewriterText;
 use crate::core::game_state::GameState;
+use crate::core::input::action::{ActionState, InputAction};
 use crate::core::language::manager::{LanguageResource, LanguageChangeEvent};
 use crate::core::language::types::LanguagePack;
+use crate::core::save::read_log::ReadLog;
 use crate::core::text::styles::TextStyleResource;
 use crate::core::text::components::TextStylePreset;
 use crate::core::text::builder::TextBuilder;
+use crate::ui::choice::ChoiceContainer;
 use crate::util::input;
-use crate::types::DialogScene;
+use crate::types::{DialogEntry, DialogScene, JumpTarget};
 
 #[derive(Component)]
 pub struct DialogBox;
@@ -26,6 +34,21 @@ pub struct DialogControls;
 #[derive(Component)]
 pub struct LanguageIndicator;
 
+/// ป้ายสถานะมุมขวาของกล่องข้อความ แสดงเมื่อเงื่อนไขของป้ายนั้นเป็นจริง
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum DialogIndicator {
+    /// บรรทัดนี้ยังไม่เคยอ่าน
+    Unread,
+    Skip,
+    Auto,
+}
+
+/// กล่องข้อความและตัวเลือกถูกซ่อนด้วย `InputAction::HideUi` เพื่อดูฉาก
+#[derive(Resource, Default)]
+pub struct DialogUiHidden {
+    pub hidden: bool,
+}
+
 const DIALOG_Z_LAYER: f32 = 10.0;
 const DIALOG_BG_COLOR: Color = Color::srgba(0.05, 0.05, 0.1, 0.85);
 const DIALOG_BORDER_COLOR: Color = Color::srgba(0.3, 0.3, 0.5, 0.5);
@@ -132,18 +155,61 @@ pub fn setup_dialog_ui(
                     &text_styles,
                     (LanguageIndicator, Name::new("language_indicator")),
                 );
+
+                controls.spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        ..default()
+                    },
+                    ..default()
+                }).with_children(|indicators| {
+                    TextBuilder::localized_child_with_components(
+                        indicators,
+                        "dialog.unread_indicator",
+                        TextStylePreset::Custom(20.0, true, Color::srgb(1.0, 0.8, 0.2)),
+                        &language_resource,
+                        &language_packs,
+                        &text_styles,
+                        (DialogIndicator::Unread, Name::new("unread_indicator")),
+                    );
+                    TextBuilder::localized_child_with_components(
+                        indicators,
+                        "dialog.skip_indicator",
+                        TextStylePreset::Custom(20.0, true, Color::srgba(0.8, 0.8, 0.9, 0.8)),
+                        &language_resource,
+                        &language_packs,
+                        &text_styles,
+                        (DialogIndicator::Skip, Name::new("skip_indicator")),
+                    );
+                    TextBuilder::localized_child_with_components(
+                        indicators,
+                        "dialog.auto_indicator",
+                        TextStylePreset::Custom(20.0, true, Color::srgba(0.8, 0.8, 0.9, 0.8)),
+                        &language_resource,
+                        &language_packs,
+                        &text_styles,
+                        (DialogIndicator::Auto, Name::new("auto_indicator")),
+                    );
+                });
             });
         });
 }
 
 pub fn handle_text_interaction(
     mut state: ResMut<VNState>,
-    mut history: ResMut<DialogHistory>,
     mut dialog_resource: ResMut<DialogResource>,
+    mut variables: ResMut<StoryVariables>,
+    mut action_events: EventWriter<DialogActionEvent>,
     dialog_scenes: Res<Assets<DialogScene>>,
     choice_state: Res<ChoiceState>,
-    mouse: Res<ButtonInput<MouseButton>>,
-    touch: Res<Touches>,
+    action_wait: Res<ActionWait>,
+    transition_state: Res<TransitionState>,
+    skip: Res<SkipState>,
+    auto: Res<AutoState>,
+    actions: Res<ActionState>,
+    ui_hidden: Res<DialogUiHidden>,
     dialog_box_query: Query<&Interaction, (With<DialogBox>, Changed<Interaction>)>,
     mut dialog_query: Query<(&mut Text, &mut TypewriterText), With<DialogText>>,
     current_state: Res<State<GameState>>,
@@ -152,11 +218,17 @@ pub fn handle_text_interaction(
         return;
     }
 
-    if choice_state.active {
+    if choice_state.active || action_wait.is_waiting() || transition_state.is_playing() || ui_hidden.hidden {
         return;
     }
 
-    if let Some(_) = input::detect_interaction(&mouse, &touch, &dialog_box_query) {
+    let clicked = input::detect_interaction(&actions, &dialog_box_query).is_some();
+    // `{nw}`: พิมพ์จบแล้วไปบรรทัดถัดไปเองโดยไม่ต้องคลิก
+    let no_wait = dialog_query
+        .get_single()
+        .is_ok_and(|(_, typewriter)| typewriter.no_wait && input::is_dialog_text_finished(typewriter));
+
+    if clicked || no_wait || skip.advance || auto.advance {
         if let Some(scene_handle) = &dialog_resource.current_scene {
             if let Some(scene) = dialog_scenes.get(scene_handle) {
                 let is_finished = {
@@ -166,14 +238,24 @@ pub fn handle_text_interaction(
                         false
                     }
                 };
+                // entry ที่มีตัวเลือกต้องรอให้ผู้เล่นเลือกเสมอ
+                if !clicked && scene.entries.get(state.stage).is_some_and(|entry| !entry.choices.is_empty()) {
+                    return;
+                }
 
-                if is_finished {
-                    process_stage_progression(&mut state, &mut history, &mut dialog_resource, scene);
+                if is_finished || skip.advance {
+                    process_stage_progression(
+                        &mut state,
+                        &mut dialog_resource,
+                        &mut variables,
+                        &mut action_events,
+                        &dialog_scenes,
+                        scene,
+                    );
                 } else {
                     if let Ok((mut text, mut typewriter)) = dialog_query.get_single_mut() {
-                        text.sections[0].value = typewriter.full_text.clone();
-                        typewriter.current_text = typewriter.full_text.clone();
-                        typewriter.char_index = typewriter.full_text.chars().count();
+                        typewriter.skip_to_wait();
+                        typewriter.reveal(&mut text);
                     }
                 }
             }
@@ -181,40 +263,128 @@ pub fn handle_text_interaction(
     }
 }
 
+/// ทำ action ของ entry ปัจจุบันแล้วไป entry ถัดไป คืน false ถ้าเรื่องหยุดเพราะกระโดดไม่สำเร็จ
 fn process_stage_progression(
     state: &mut VNState,
-    history: &mut DialogHistory,
     dialog_resource: &mut DialogResource,
+    variables: &mut StoryVariables,
+    action_events: &mut EventWriter<DialogActionEvent>,
+    dialog_scenes: &Assets<DialogScene>,
     scene: &DialogScene,
-) {
+) -> bool {
     if state.stage < scene.entries.len() {
         let entry = &scene.entries[state.stage];
 
-        if !entry.actions.is_empty() {
-            for action in &entry.actions {
-                if let Some(scene_name) = action.strip_prefix("change_scene:") {
-                    if dialog_resource.change_scene(scene_name, state) {
-                        return;
+        for action in &entry.actions {
+            match action {
+                DialogAction::SetVar { name, value } => {
+                    variables.set(name, value.clone());
+                }
+                DialogAction::AddVar { name, amount } => {
+                    let value = variables.get_int(name) + amount;
+                    variables.set_int(name, value);
+                }
+                DialogAction::Jump { target, condition } => {
+                    let passed = condition.as_ref().is_none_or(|condition| condition.evaluate(variables));
+                    if passed {
+                        if !dialog_resource.jump(target, state, dialog_scenes) {
+                            return stop_story(state, action_events);
+                        }
+                        return true;
+                    }
+                }
+                DialogAction::ChangeScene { scene: scene_name, label } => {
+                    let target = JumpTarget::Scene {
+                        scene: scene_name.clone(),
+                        label: label.clone(),
+                    };
+                    if !dialog_resource.jump(&target, state, dialog_scenes) {
+                        return stop_story(state, action_events);
+                    }
+                    return true;
+                }
+                DialogAction::CallScene { scene: scene_name, label } => {
+                    if !dialog_resource.call_scene(scene_name, label.as_deref(), state, dialog_scenes) {
+                        return stop_story(state, action_events);
+                    }
+                    return true;
+                }
+                DialogAction::Return => {
+                    if dialog_resource.return_from_scene(state, dialog_scenes) {
+                        return true;
                     }
                 }
+                _ => {
+                    action_events.send(DialogActionEvent { action: action.clone() });
+                }
             }
         }
     }
 
     let auto_target = if state.stage < scene.entries.len() {
-        scene.entries[state.stage].auto_proceed
+        scene.entries[state.stage].auto_proceed.clone()
     } else {
         None
     };
 
-    history.add_stage(state.stage);
-
     if let Some(target) = auto_target {
-        state.change_stage(target);
+        if !dialog_resource.jump(&target, state, dialog_scenes) {
+            return stop_story(state, action_events);
+        }
+    } else if state.stage + 1 >= scene.entries.len() && !state.call_stack.is_empty() {
+        // sub-scene เล่นจนจบโดยไม่มี `return` ก็กลับไปหาผู้เรียก
+        dialog_resource.return_from_scene(state, dialog_scenes);
     } else {
         let next_stage = (state.stage + 1) % scene.entries.len();
         state.change_stage(next_stage);
     }
+    true
+}
+
+/// กระโดดไปยัง target ที่ไม่มีอยู่ (error ถูก log ไว้แล้ว): จบเรื่องกลับเมนูหลักแทนการเล่นต่อผิดที่
+fn stop_story(state: &VNState, action_events: &mut EventWriter<DialogActionEvent>) -> bool {
+    error!("Stopping the story at stage {} of scene `{}`", state.stage, state.current_scene);
+    action_events.send(DialogActionEvent { action: DialogAction::End });
+    false
+}
+
+/// จำนวน control entry สูงสุดที่ผ่านได้ในหนึ่ง frame กันวนไม่รู้จบ
+const MAX_CONTROL_STEPS: usize = 64;
+
+/// ผ่าน control entry (ไม่มีข้อความ) ต่อกันทันที ก่อน `manage_dialog_state` จะแสดงผล
+pub fn advance_control_entries(
+    mut state: ResMut<VNState>,
+    mut dialog_resource: ResMut<DialogResource>,
+    mut variables: ResMut<StoryVariables>,
+    mut action_events: EventWriter<DialogActionEvent>,
+    dialog_scenes: Res<Assets<DialogScene>>,
+) {
+    for _ in 0..MAX_CONTROL_STEPS {
+        let Some(scene) = dialog_resource
+            .current_scene
+            .as_ref()
+            .and_then(|handle| dialog_scenes.get(handle))
+        else {
+            return;
+        };
+
+        if !scene.entries.get(state.stage).is_some_and(DialogEntry::is_control) {
+            return;
+        }
+
+        if !process_stage_progression(
+            &mut state,
+            &mut dialog_resource,
+            &mut variables,
+            &mut action_events,
+            &dialog_scenes,
+            scene,
+        ) {
+            return;
+        }
+    }
+
+    warn!("Stopped after {} control entries in a row at stage {}", MAX_CONTROL_STEPS, state.stage);
 }
 
 /// Update dialog text fonts เมื่อเปลี่ยนภาษา
@@ -223,7 +393,7 @@ pub fn update_dialog_fonts(
     language_resource: Res<LanguageResource>,
     text_styles: Res<TextStyleResource>,
     mut character_name_query: Query<&mut Text, (With<CharacterName>, Without<DialogText>)>,
-    mut dialog_text_query: Query<&mut Text, (With<DialogText>, Without<CharacterName>)>,
+    mut dialog_text_query: Query<(&mut Text, &TypewriterText), With<DialogText>>,
 ) {
     for _event in language_events.read() {
         for mut text in character_name_query.iter_mut() {
@@ -233,34 +403,66 @@ pub fn update_dialog_fonts(
         }
 
         // Update dialog text font
-        for mut text in dialog_text_query.iter_mut() {
-            if !text.sections.is_empty() {
-                text.sections[0].style = TextStylePreset::DialogText.to_style(&text_styles, &language_resource.current_language);
-            }
+        for (mut text, typewriter) in dialog_text_query.iter_mut() {
+            text.sections = typewriter.sections(&TextStylePreset::DialogText, &text_styles, &language_resource.current_language);
+            typewriter.reveal(&mut text);
         }
     }
 }
 
+/// ซ่อน/แสดงกล่องข้อความ ตอนซ่อนอยู่กด `Advance` ก็แสดงกลับ (ต้องรันหลัง `handle_text_interaction`)
+pub fn toggle_dialog_ui(
+    actions: Res<ActionState>,
+    mut ui_hidden: ResMut<DialogUiHidden>,
+    mut dialog_query: Query<&mut Visibility, With<DialogBox>>,
+    mut choice_query: Query<&mut Visibility, (With<ChoiceContainer>, Without<DialogBox>)>,
+) {
+    let toggle = actions.just_pressed(InputAction::HideUi)
+        || (ui_hidden.hidden && actions.just_pressed(InputAction::Advance));
+    if !toggle {
+        return;
+    }
+
+    ui_hidden.hidden = !ui_hidden.hidden;
+    let visibility = if ui_hidden.hidden { Visibility::Hidden } else { Visibility::Inherited };
+    for mut dialog_visibility in dialog_query.iter_mut().chain(choice_query.iter_mut()) {
+        *dialog_visibility = visibility;
+    }
+}
+
+/// ป้ายข้ามข้อความ, โหมด auto และบรรทัดที่ยังไม่เคยอ่าน
+pub fn update_dialog_indicators(
+    state: Res<VNState>,
+    settings: Res<SettingsResource>,
+    read_log: Res<ReadLog>,
+    skip: Res<SkipState>,
+    auto: Res<AutoState>,
+    mut indicator_query: Query<(&DialogIndicator, &mut Visibility)>,
+) {
+    let unread = settings.mark_unread && !read_log.is_read(&state.current_scene, state.stage);
+    for (indicator, mut visibility) in indicator_query.iter_mut() {
+        let shown = match indicator {
+            DialogIndicator::Unread => unread,
+            DialogIndicator::Skip => skip.is_active(),
+            DialogIndicator::Auto => auto.enabled,
+        };
+        visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+    }
+}
+
 pub fn paused_typewriter_system(
     time: Res<Time>,
     mut query: Query<(&mut Text, &mut TypewriterText)>,
     current_state: Res<State<GameState>>,
+    action_wait: Res<ActionWait>,
 ) {
-    if *current_state.get() != GameState::InGame {
+    if *current_state.get() != GameState::InGame || action_wait.is_waiting() {
         return;
     }
 
     for (mut text, mut typewriter) in query.iter_mut() {
-        if typewriter.char_index < typewriter.full_text.chars().count() {
-            typewriter.timer.tick(time.delta());
-
-            if typewriter.timer.just_finished() {
-                if let Some(next_char) = typewriter.full_text.chars().nth(typewriter.char_index) {
-                    typewriter.current_text.push(next_char);
-                    typewriter.char_index += 1;
-                    text.sections[0].value = typewriter.current_text.clone();
-                }
-            }
+        if typewriter.tick(time.delta()) {
+            typewriter.reveal(&mut text);
         }
     }
 }
@@ -0,0 +1,157 @@
+//! focus ของปุ่มในเมนูและตัวเลือก สำหรับเล่นด้วยคีย์บอร์ดหรือ gamepad
+//!
+//! `Navigate*` (ลูกศร / D-pad) หรือ `FocusNext` / `FocusPrev` (Tab) ย้าย focus ตามลำดับบนจอ (บนลงล่าง ซ้ายไปขวา
+//! วนกลับเมื่อสุดรายการ) และ `Advance` (Enter / Pad A) กดปุ่มที่ focus อยู่ โดยตั้ง `Interaction::Pressed`
+//! ให้ปุ่มนั้น ระบบปุ่มเดิมที่อ่าน `Changed<Interaction>` จึงทำงานได้โดยไม่ต้องแก้
+//! ปุ่มที่ focus อยู่ใช้สี hover ของหน้านั้น ขยับเมาส์ คลิก หรือแตะจอจะยกเลิก focus
+
+use bevy::prelude::*;
+use bevy::window::CursorMoved;
+use crate::core::game_state::GameState;
+use crate::core::input::action::{ActionState, InputAction};
+
+/// ปุ่มที่เลือกด้วย `Navigate*` ได้ เฉพาะตอนอยู่ใน `state` (หน้าที่แสดงทับกันจึงไม่ปนกัน)
+#[derive(Component)]
+pub struct Focusable {
+    pub state: GameState,
+    /// สีปกติและสี hover ของปุ่ม (ตอน focus ใช้สี hover)
+    pub color: Color,
+    pub hover_color: Color,
+}
+
+impl Focusable {
+    pub fn new(state: GameState, color: Color, hover_color: Color) -> Self {
+        Self { state, color, hover_color }
+    }
+}
+
+/// ปุ่มที่ใช้ซ้าย/ขวาปรับค่าแทนการย้าย focus และไม่ถูกกดด้วย `Advance` (เช่น slider)
+#[derive(Component)]
+pub struct FocusAdjustable;
+
+#[derive(Resource, Default)]
+pub struct FocusState {
+    pub focused: Option<Entity>,
+    /// ซ้าย/ขวาบนปุ่ม `FocusAdjustable` ที่ focus อยู่ใน frame นี้ (-1, 0 หรือ 1)
+    pub adjust: i8,
+    /// หยุดย้าย/กดด้วย focus ชั่วคราว เช่น ตอนหน้าตั้งค่าปุ่มรอรับปุ่มใหม่
+    pub suspended: bool,
+    /// ปุ่มที่กดผ่าน focus ใน frame ก่อน ต้องคืนเป็น `Interaction::None`
+    activated: Option<Entity>,
+}
+
+/// ย้าย focus และกดปุ่ม (รันใน `PreUpdate` หลัง `update_action_state` และ `UiSystem::Focus`)
+pub fn navigate_focus(
+    actions: Res<ActionState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touch: Res<Touches>,
+    current_state: Res<State<GameState>>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut focus: ResMut<FocusState>,
+    focusable_query: Query<(Entity, &Focusable, &GlobalTransform, &InheritedVisibility, Has<FocusAdjustable>)>,
+    mut interaction_query: Query<&mut Interaction>,
+) {
+    let activated = focus.activated.take().and_then(|entity| interaction_query.get_mut(entity).ok());
+    if let Some(mut interaction) = activated.filter(|interaction| **interaction == Interaction::Pressed) {
+        *interaction = Interaction::None;
+    }
+    focus.adjust = 0;
+    if focus.suspended {
+        return;
+    }
+
+    let pointer_used = cursor_moved.read().count() > 0
+        || mouse.get_just_pressed().len() > 0
+        || touch.iter_just_pressed().next().is_some();
+    if pointer_used {
+        focus.focused = None;
+        return;
+    }
+
+    let mut targets: Vec<(Entity, Vec2, bool)> = focusable_query
+        .iter()
+        .filter(|(_, focusable, _, visibility, _)| focusable.state == *current_state.get() && visibility.get())
+        .map(|(entity, _, transform, _, adjustable)| (entity, transform.translation().truncate(), adjustable))
+        .collect();
+    targets.sort_by(|(_, a, _), (_, b, _)| a.y.round().total_cmp(&b.y.round()).then(a.x.total_cmp(&b.x)));
+
+    let current = focus
+        .focused
+        .and_then(|focused| targets.iter().position(|(entity, ..)| *entity == focused));
+    if current.is_none() {
+        focus.focused = None;
+    }
+    if targets.is_empty() {
+        return;
+    }
+
+    let horizontal: i8 = if actions.just_pressed(InputAction::NavigateLeft) {
+        -1
+    } else if actions.just_pressed(InputAction::NavigateRight) {
+        1
+    } else {
+        0
+    };
+    let adjustable = current.is_some_and(|index| targets[index].2);
+    let step = if actions.just_pressed(InputAction::NavigateUp) || actions.just_pressed(InputAction::FocusPrev) {
+        -1
+    } else if actions.just_pressed(InputAction::NavigateDown) || actions.just_pressed(InputAction::FocusNext) {
+        1
+    } else if adjustable {
+        focus.adjust = horizontal;
+        0
+    } else {
+        horizontal
+    };
+
+    if step != 0 {
+        let next = match current {
+            Some(index) => (index as isize + step as isize).rem_euclid(targets.len() as isize) as usize,
+            None if step > 0 => 0,
+            None => targets.len() - 1,
+        };
+        focus.focused = Some(targets[next].0);
+    } else if actions.just_pressed(InputAction::Advance) {
+        match current {
+            Some(index) if !adjustable => {
+                let entity = targets[index].0;
+                if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+                    *interaction = Interaction::Pressed;
+                    focus.activated = Some(entity);
+                }
+            }
+            Some(_) => {}
+            // ยังไม่มี focus: กดครั้งแรกเลือกปุ่มแรกก่อน
+            None => focus.focused = Some(targets[0].0),
+        }
+    }
+}
+
+/// ใช้สี hover กับปุ่มที่ focus อยู่ และคืนสีเดิมให้ปุ่มที่เพิ่งเสีย focus
+/// (รันหลังระบบ hover ของแต่ละหน้าซึ่งตั้งสีตาม `Interaction` ไปแล้ว)
+pub fn update_focus_highlight(
+    focus: Res<FocusState>,
+    mut highlighted: Local<Option<Entity>>,
+    mut button_query: Query<(&Focusable, &Interaction, &mut BackgroundColor)>,
+) {
+    if *highlighted != focus.focused {
+        if let Some((focusable, interaction, mut bg_color)) = highlighted.and_then(|entity| button_query.get_mut(entity).ok()) {
+            match interaction {
+                Interaction::Hovered => *bg_color = focusable.hover_color.into(),
+                Interaction::None => *bg_color = focusable.color.into(),
+                Interaction::Pressed => {}
+            }
+        }
+        *highlighted = focus.focused;
+    }
+
+    let focused = focus
+        .focused
+        .and_then(|entity| button_query.get_mut(entity).ok())
+        .filter(|(focusable, interaction, bg_color)| {
+            **interaction != Interaction::Pressed && bg_color.0 != focusable.hover_color
+        });
+    if let Some((focusable, _, mut bg_color)) = focused {
+        *bg_color = focusable.hover_color.into();
+    }
+}
@@ -0,0 +1,370 @@
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use crate::core::game_state::{GameState, ChangeStateEvent};
+use crate::core::input::action::{ActionState, InputAction, InputMap};
+use crate::core::input::binding::{InputBinding, BINDABLE_GAMEPAD_BUTTONS, BINDABLE_KEYS};
+use crate::core::language::manager::{LanguageResource, get_text};
+use crate::core::language::types::LanguagePack;
+use crate::core::resources::SettingsResource;
+use crate::core::text::builder::TextBuilder;
+use crate::core::text::components::TextStylePreset;
+use crate::core::text::styles::TextStyleResource;
+use crate::ui::focus::{FocusState, Focusable};
+
+#[derive(Component)]
+pub struct KeyBindingsUI;
+
+/// แถวของ action คลิกแล้วรอปุ่มใหม่
+#[derive(Component)]
+pub struct BindingButton {
+    pub action: InputAction,
+}
+
+#[derive(Component)]
+pub struct BindingText {
+    pub action: InputAction,
+}
+
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+#[derive(Component)]
+pub struct KeyBindingsBackButton;
+
+/// action ที่กำลังรอให้ผู้เล่นกดปุ่มใหม่
+#[derive(Resource, Default)]
+pub struct RebindState {
+    pub listening: Option<InputAction>,
+}
+
+const KEY_BINDINGS_BG_COLOR: Color = Color::srgba(0.08, 0.08, 0.15, 1.0);
+const PANEL_BG_COLOR: Color = Color::srgba(0.15, 0.15, 0.25, 0.95);
+const BUTTON_COLOR: Color = Color::srgba(0.2, 0.2, 0.3, 0.9);
+const BUTTON_HOVER: Color = Color::srgba(0.3, 0.3, 0.4, 0.9);
+const BUTTON_PRESSED: Color = Color::srgba(0.4, 0.4, 0.5, 0.9);
+const LISTENING_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const BINDING_COLOR: Color = Color::srgba(0.8, 0.8, 0.9, 1.0);
+/// ปุ่มเมาส์ที่ตั้งเป็นปุ่มได้ (คลิกซ้ายใช้เลือกแถวจึงไม่รับ)
+const REBINDABLE_MOUSE: [MouseButton; 2] = [MouseButton::Right, MouseButton::Middle];
+
+pub fn setup_key_bindings_ui(
+    mut commands: Commands,
+    mut rebind: ResMut<RebindState>,
+    settings: Res<SettingsResource>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    text_styles: Res<TextStyleResource>,
+) {
+    rebind.listening = None;
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: KEY_BINDINGS_BG_COLOR.into(),
+            ..default()
+        },
+        KeyBindingsUI,
+        Name::new("key_bindings"),
+    )).with_children(|background| {
+        background.spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(760.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(24.0)),
+                row_gap: Val::Px(14.0),
+                ..default()
+            },
+            background_color: PANEL_BG_COLOR.into(),
+            border_radius: BorderRadius::all(Val::Px(15.0)),
+            ..default()
+        }).with_children(|panel| {
+            TextBuilder::localized_child(
+                panel,
+                "ui.key_bindings_title",
+                TextStylePreset::Title,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+
+            // รายการ action (2 คอลัมน์)
+            panel.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::SpaceBetween,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                ..default()
+            }).with_children(|grid| {
+                for action in InputAction::all() {
+                    grid.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(350.0),
+                                height: Val::Px(34.0),
+                                flex_direction: FlexDirection::Row,
+                                justify_content: JustifyContent::SpaceBetween,
+                                align_items: AlignItems::Center,
+                                padding: UiRect::horizontal(Val::Px(12.0)),
+                                ..default()
+                            },
+                            background_color: BUTTON_COLOR.into(),
+                            border_radius: BorderRadius::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        BindingButton { action },
+                        Focusable::new(GameState::KeyBindings, BUTTON_COLOR, BUTTON_HOVER),
+                    )).with_children(|row| {
+                        TextBuilder::static_child(
+                            row,
+                            &action_label(action, &language_resource, &language_packs),
+                            TextStylePreset::Custom(16.0, true, Color::WHITE),
+                            &language_resource,
+                            &text_styles,
+                        );
+                        TextBuilder::static_child_with_components(
+                            row,
+                            &bindings_label(&settings.bindings, action),
+                            TextStylePreset::Custom(16.0, false, BINDING_COLOR),
+                            &language_resource,
+                            &text_styles,
+                            BindingText { action },
+                        );
+                    });
+                }
+            });
+
+            TextBuilder::localized_child(
+                panel,
+                "ui.key_bindings_hint",
+                TextStylePreset::Hint,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+
+            panel.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::SpaceEvenly,
+                    ..default()
+                },
+                ..default()
+            }).with_children(|buttons| {
+                spawn_button(buttons, "ui.back", KeyBindingsBackButton, &language_resource, &language_packs, &text_styles);
+                spawn_button(buttons, "ui.reset_defaults", ResetBindingsButton, &language_resource, &language_packs, &text_styles);
+            });
+        });
+    });
+}
+
+fn spawn_button<T: Component>(
+    parent: &mut ChildBuilder,
+    text_key: &str,
+    component: T,
+    language_resource: &LanguageResource,
+    language_packs: &Assets<LanguagePack>,
+    text_styles: &TextStyleResource,
+) {
+    parent.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(140.0),
+                height: Val::Px(45.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BUTTON_COLOR.into(),
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        component,
+        Focusable::new(GameState::KeyBindings, BUTTON_COLOR, BUTTON_HOVER),
+    )).with_children(|button| {
+        TextBuilder::localized_child(
+            button,
+            text_key,
+            TextStylePreset::Custom(18.0, false, Color::WHITE),
+            language_resource,
+            language_packs,
+            text_styles,
+        );
+    });
+}
+
+/// ชื่อ action ในภาษาปัจจุบัน (`Choice` ต่อท้ายด้วยเลข)
+fn action_label(action: InputAction, language_resource: &LanguageResource, language_packs: &Assets<LanguagePack>) -> String {
+    let name = get_text(language_resource, language_packs, action.text_key());
+    match action {
+        InputAction::Choice(number) => format!("{} {}", name, number),
+        _ => name,
+    }
+}
+
+fn bindings_label(bindings: &InputMap, action: InputAction) -> String {
+    let labels: Vec<String> = bindings.bindings(action).iter().map(InputBinding::label).collect();
+    if labels.is_empty() {
+        "-".to_string()
+    } else {
+        labels.join(" / ")
+    }
+}
+
+pub fn handle_key_bindings_button_hover(
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut bg_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => *bg_color = BUTTON_PRESSED.into(),
+            Interaction::Hovered => *bg_color = BUTTON_HOVER.into(),
+            Interaction::None => *bg_color = BUTTON_COLOR.into(),
+        }
+    }
+}
+
+pub fn handle_key_bindings_buttons(
+    binding_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    reset_query: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<KeyBindingsBackButton>)>,
+    mut rebind: ResMut<RebindState>,
+    mut settings: ResMut<SettingsResource>,
+    mut focus: ResMut<FocusState>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    for (interaction, button) in binding_query.iter() {
+        if *interaction == Interaction::Pressed {
+            rebind.listening = Some(button.action);
+        }
+    }
+
+    for interaction in reset_query.iter() {
+        if *interaction == Interaction::Pressed {
+            rebind.listening = None;
+            settings.bindings = InputMap::default();
+        }
+    }
+
+    for interaction in back_query.iter() {
+        if *interaction == Interaction::Pressed {
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::Settings,
+            });
+        }
+    }
+
+    // ตอนรอรับปุ่มใหม่ Enter/ลูกศรต้องไม่ย้ายหรือกดปุ่มที่ focus อยู่
+    focus.suspended = rebind.listening.is_some();
+}
+
+/// รับปุ่มใหม่ให้ action ที่เลือกไว้ ESC ยกเลิก ถ้าไม่ได้รออยู่ `Back` กลับไปหน้า Settings
+///
+/// ต้องรันก่อน `handle_key_bindings_buttons` เพื่อไม่ให้ปุ่มที่ใช้เลือกแถว (เช่น Pad A) ถูกตั้งเป็นปุ่มใหม่ทันที
+pub fn handle_rebind_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    actions: Res<ActionState>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut rebind: ResMut<RebindState>,
+    mut settings: ResMut<SettingsResource>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    let wheel = mouse_wheel.read().fold(None, |wheel, event| match event.y {
+        y if y > 0.0 => Some(InputBinding::WheelUp),
+        y if y < 0.0 => Some(InputBinding::WheelDown),
+        _ => wheel,
+    });
+
+    let Some(action) = rebind.listening else {
+        if actions.just_pressed(InputAction::Back) {
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::Settings,
+            });
+        }
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Escape) {
+        rebind.listening = None;
+        return;
+    }
+
+    let pressed = BINDABLE_KEYS
+        .iter()
+        .find(|key| keyboard.just_pressed(**key))
+        .map(|key| InputBinding::Key(*key))
+        .or_else(|| {
+            REBINDABLE_MOUSE
+                .iter()
+                .find(|button| mouse.just_pressed(**button))
+                .map(|button| InputBinding::Mouse(*button))
+        })
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .map(|button| button.button_type)
+                .find(|button_type| BINDABLE_GAMEPAD_BUTTONS.contains(button_type))
+                .map(InputBinding::Gamepad)
+        })
+        .or(wheel);
+
+    if let Some(binding) = pressed {
+        settings.bindings.rebind(action, binding);
+        rebind.listening = None;
+        info!("Bound {} to {}", binding, action);
+    }
+}
+
+/// เขียนปุ่มของทุกแถวใหม่เมื่อตารางปุ่มหรือแถวที่รอเปลี่ยน
+pub fn update_binding_texts(
+    rebind: Res<RebindState>,
+    settings: Res<SettingsResource>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    mut text_query: Query<(&mut Text, &BindingText)>,
+) {
+    if !rebind.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    for (mut text, binding_text) in text_query.iter_mut() {
+        let (value, color) = if rebind.listening == Some(binding_text.action) {
+            (get_text(&language_resource, &language_packs, "ui.press_key"), LISTENING_COLOR)
+        } else {
+            (bindings_label(&settings.bindings, binding_text.action), BINDING_COLOR)
+        };
+        if let Some(section) = text.sections.first_mut() {
+            section.value = value;
+            section.style.color = color;
+        }
+    }
+}
+
+/// ลบหน้าตั้งค่าปุ่มและบันทึกตารางปุ่มลงไฟล์ settings ทันที (ไม่ต้องกด Apply)
+pub fn cleanup_key_bindings_ui(
+    mut commands: Commands,
+    settings: Res<SettingsResource>,
+    mut focus: ResMut<FocusState>,
+    key_bindings_query: Query<Entity, With<KeyBindingsUI>>,
+) {
+    focus.suspended = false;
+    for entity in key_bindings_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Err(err) = settings.save() {
+        error!("Failed to save key bindings: {:#}", err);
+    }
+}
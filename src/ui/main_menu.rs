@@ -1,10 +1,16 @@
 use bevy::prelude::*;
+use bevy::asset::LoadState;
 use crate::core::game_state::{GameState, ChangeStateEvent};
+use crate::core::resources::DialogResource;
+use crate::core::save::manager::NewGameEvent;
+use crate::core::story::manifest::{StoryResource, STORY_MANIFEST};
 use crate::core::language::manager::LanguageResource;
 use crate::core::language::types::LanguagePack;
 use crate::core::text::styles::TextStyleResource;
 use crate::core::text::components::TextStylePreset;
 use crate::core::text::builder::TextBuilder;
+use crate::ui::focus::Focusable;
+use crate::ui::save_load::SaveLoadMode;
 
 #[derive(Component)]
 pub struct MainMenuUI;
@@ -12,6 +18,9 @@ pub struct MainMenuUI;
 #[derive(Component)]
 pub struct StartGameButton;
 
+#[derive(Component)]
+pub struct LoadGameButton;
+
 #[derive(Component)]
 pub struct SettingsButton;
 
@@ -101,6 +110,16 @@ pub fn setup_main_menu(
             &text_styles,
         );
 
+        // Load Game Button
+        create_menu_button(
+            parent,
+            "ui.load_game",
+            LoadGameButton,
+            &language_resource,
+            &language_packs,
+            &text_styles,
+        );
+
         // Settings Button
         create_menu_button(
             parent,
@@ -170,6 +189,7 @@ fn create_menu_button<T: Component>(
             ..default()
         },
         button_component,
+        Focusable::new(GameState::MainMenu, MENU_BUTTON_COLOR, MENU_BUTTON_HOVER),
         Name::new("menu_button"),
     )).with_children(|button| {
         TextBuilder::localized_child(
@@ -186,7 +206,7 @@ fn create_menu_button<T: Component>(
 pub fn handle_menu_button_hover(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, Or<(With<StartGameButton>, With<SettingsButton>, With<ExitGameButton>)>)
+        (Changed<Interaction>, Or<(With<StartGameButton>, With<LoadGameButton>, With<SettingsButton>, With<ExitGameButton>)>)
     >,
 ) {
     for (interaction, mut bg_color) in button_query.iter_mut() {
@@ -206,15 +226,25 @@ pub fn handle_menu_button_hover(
 
 pub fn handle_menu_buttons(
     start_query: Query<&Interaction, (Changed<Interaction>, With<StartGameButton>)>,
+    load_query: Query<&Interaction, (Changed<Interaction>, With<LoadGameButton>)>,
     settings_query: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
     exit_query: Query<&Interaction, (Changed<Interaction>, With<ExitGameButton>)>,
+    mut save_load_mode: ResMut<SaveLoadMode>,
+    mut new_game_events: EventWriter<NewGameEvent>,
     mut change_events: EventWriter<ChangeStateEvent>,
     mut exit: EventWriter<AppExit>,
 ) {
     for interaction in start_query.iter() {
         if *interaction == Interaction::Pressed {
+            new_game_events.send(NewGameEvent);
+        }
+    }
+
+    for interaction in load_query.iter() {
+        if *interaction == Interaction::Pressed {
+            *save_load_mode = SaveLoadMode::Load;
             change_events.send(ChangeStateEvent {
-                new_state: GameState::Loading,
+                new_state: GameState::SaveLoad,
             });
         }
     }
@@ -297,8 +327,21 @@ pub fn setup_loading_screen(
 pub fn handle_loading_transition(
     mut timer: Local<Option<Timer>>,
     time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    story: Res<StoryResource>,
+    dialog_resource: Res<DialogResource>,
     mut change_events: EventWriter<ChangeStateEvent>,
 ) {
+    // story.json โหลดไม่ผ่าน scene เริ่มต้นจะไม่มีวันถูกลงทะเบียน: กลับเมนูหลักแทนการรอไปเรื่อยๆ
+    if let LoadState::Failed(e) = asset_server.load_state(&story.manifest) {
+        error!("Cannot start the story, failed to load {}: {}", STORY_MANIFEST, e);
+        change_events.send(ChangeStateEvent {
+            new_state: GameState::MainMenu,
+        });
+        *timer = None;
+        return;
+    }
+
     if timer.is_none() {
         *timer = Some(Timer::from_seconds(2.0, TimerMode::Once));
     }
@@ -306,7 +349,8 @@ pub fn handle_loading_transition(
     if let Some(ref mut loading_timer) = timer.as_mut() {
         loading_timer.tick(time.delta());
 
-        if loading_timer.finished() {
+        // รอจน story.json ลงทะเบียน scene เริ่มต้นแล้ว
+        if loading_timer.finished() && dialog_resource.current_scene.is_some() {
             change_events.send(ChangeStateEvent {
                 new_state: GameState::InGame,
             });
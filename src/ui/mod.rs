@@ -2,4 +2,8 @@ pub mod dialog;
 pub mod choice;
 pub mod main_menu;
 pub mod settings;
-pub mod pause;
\ No newline at end of file
+pub mod pause;
+pub mod save_load;
+pub mod backlog;
+pub mod key_bindings;
+pub mod focus;
\ No newline at end of file
@@ -5,6 +5,8 @@ use crate::core::language::types::LanguagePack;
 use crate::core::text::styles::TextStyleResource;
 use crate::core::text::components::TextStylePreset;
 use crate::core::text::builder::TextBuilder;
+use crate::ui::focus::Focusable;
+use crate::ui::save_load::SaveLoadMode;
 
 #[derive(Component)]
 pub struct PauseUI;
@@ -12,6 +14,12 @@ pub struct PauseUI;
 #[derive(Component)]
 pub struct ResumeButton;
 
+#[derive(Component)]
+pub struct SaveGameButton;
+
+#[derive(Component)]
+pub struct LoadGameButton;
+
 #[derive(Component)]
 pub struct MainMenuButton;
 
@@ -88,6 +96,24 @@ pub fn setup_pause_ui(
                 &text_styles,
             );
 
+            // Save / Load Buttons
+            create_pause_button(
+                panel,
+                "ui.save_game",
+                SaveGameButton,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+            create_pause_button(
+                panel,
+                "ui.load_game",
+                LoadGameButton,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+
             // Main Menu Button
             create_pause_button(
                 panel,
@@ -146,6 +172,7 @@ fn create_pause_button<T: Component>(
             ..default()
         },
         button_component,
+        Focusable::new(GameState::Paused, PAUSE_BUTTON_COLOR, PAUSE_BUTTON_HOVER),
         Name::new("pause_button"),
     )).with_children(|button| {
         TextBuilder::localized_child(
@@ -162,7 +189,7 @@ fn create_pause_button<T: Component>(
 pub fn handle_pause_button_hover(
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, Or<(With<ResumeButton>, With<MainMenuButton>)>)
+        (Changed<Interaction>, Or<(With<ResumeButton>, With<SaveGameButton>, With<LoadGameButton>, With<MainMenuButton>)>)
     >,
 ) {
     for (interaction, mut bg_color) in button_query.iter_mut() {
@@ -182,7 +209,10 @@ pub fn handle_pause_button_hover(
 
 pub fn handle_pause_buttons(
     resume_query: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    save_query: Query<&Interaction, (Changed<Interaction>, With<SaveGameButton>)>,
+    load_query: Query<&Interaction, (Changed<Interaction>, With<LoadGameButton>)>,
     main_menu_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuButton>)>,
+    mut save_load_mode: ResMut<SaveLoadMode>,
     mut change_events: EventWriter<ChangeStateEvent>,
 ) {
     // Resume Game - กลับไป InGame โดยไม่ cleanup
@@ -193,6 +223,23 @@ pub fn handle_pause_buttons(
             });
         }
     }
+    // Save / Load - เปิดหน้า save/load ทับเกม
+    for interaction in save_query.iter() {
+        if *interaction == Interaction::Pressed {
+            *save_load_mode = SaveLoadMode::Save;
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::SaveLoad,
+            });
+        }
+    }
+    for interaction in load_query.iter() {
+        if *interaction == Interaction::Pressed {
+            *save_load_mode = SaveLoadMode::Load;
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::SaveLoad,
+            });
+        }
+    }
     // Main Menu - ไป MainMenu พร้อม cleanup
     for interaction in main_menu_query.iter() {
         if *interaction == Interaction::Pressed {
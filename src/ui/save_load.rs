@@ -0,0 +1,352 @@
+use bevy::prelude::*;
+use crate::core::game_state::{GameState, ChangeStateEvent, PreviousState};
+use crate::core::language::manager::{LanguageResource, get_text};
+use crate::core::language::types::LanguagePack;
+use crate::core::resources::VNState;
+use crate::core::save::data::{read_meta, format_playtime, format_timestamp, SaveMeta, SaveSlot, AUTO_SAVE_SLOTS, SAVE_SLOTS};
+use crate::core::save::manager::{SaveGameEvent, LoadGameEvent};
+use crate::core::story::manifest::{StoryManifest, StoryResource};
+use crate::core::text::styles::TextStyleResource;
+use crate::core::text::components::TextStylePreset;
+use crate::core::text::builder::TextBuilder;
+use crate::ui::focus::Focusable;
+
+/// หน้า save/load เปิดมาเพื่อบันทึกหรือโหลด
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveLoadMode {
+    Save,
+    #[default]
+    Load,
+}
+
+impl SaveLoadMode {
+    /// ช่องที่แสดง: quick save และ auto-save โหลดได้อย่างเดียว
+    fn slots(&self) -> Vec<SaveSlot> {
+        let manual = (1..=SAVE_SLOTS).map(SaveSlot::Manual);
+        match self {
+            SaveLoadMode::Save => manual.collect(),
+            SaveLoadMode::Load => std::iter::once(SaveSlot::Quick)
+                .chain((1..=AUTO_SAVE_SLOTS).map(SaveSlot::Auto))
+                .chain(manual)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SaveLoadUI;
+
+#[derive(Component)]
+pub struct SaveSlotButton {
+    pub slot: SaveSlot,
+}
+
+#[derive(Component)]
+pub struct SaveLoadBackButton;
+
+const SAVE_LOAD_Z_LAYER: f32 = 25.0;
+/// ความยาวสูงสุดของข้อความตัวอย่างในช่อง save
+const PREVIEW_CHARS: usize = 48;
+const SAVE_LOAD_OVERLAY_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+const SAVE_LOAD_PANEL_COLOR: Color = Color::srgba(0.1, 0.1, 0.15, 0.95);
+const SLOT_BUTTON_COLOR: Color = Color::srgba(0.2, 0.2, 0.3, 0.9);
+const SLOT_BUTTON_HOVER: Color = Color::srgba(0.3, 0.3, 0.4, 0.9);
+const SLOT_BUTTON_PRESSED: Color = Color::srgba(0.4, 0.4, 0.5, 0.9);
+
+pub fn setup_save_load_ui(
+    mut commands: Commands,
+    mode: Res<SaveLoadMode>,
+    state: Res<VNState>,
+    story: Res<StoryResource>,
+    manifests: Res<Assets<StoryManifest>>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    text_styles: Res<TextStyleResource>,
+) {
+    let manifest = manifests.get(&story.manifest);
+    let title_key = match *mode {
+        SaveLoadMode::Save => "ui.save_game",
+        SaveLoadMode::Load => "ui.load_game",
+    };
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: SAVE_LOAD_OVERLAY_COLOR.into(),
+            z_index: ZIndex::Global(SAVE_LOAD_Z_LAYER as i32),
+            ..default()
+        },
+        SaveLoadUI,
+        Name::new("save_load_overlay"),
+    )).with_children(|overlay| {
+        overlay.spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(780.0),
+                    height: Val::Auto,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: SAVE_LOAD_PANEL_COLOR.into(),
+                border_radius: BorderRadius::all(Val::Px(15.0)),
+                ..default()
+            },
+            Name::new("save_load_panel"),
+        )).with_children(|panel| {
+            TextBuilder::localized_child(
+                panel,
+                title_key,
+                TextStylePreset::Title,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
+
+            // Slot grid (2 คอลัมน์)
+            panel.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::SpaceBetween,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }).with_children(|grid| {
+                for slot in mode.slots() {
+                    grid.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(350.0),
+                                height: Val::Px(84.0),
+                                flex_direction: FlexDirection::Column,
+                                justify_content: JustifyContent::Center,
+                                padding: UiRect::horizontal(Val::Px(15.0)),
+                                row_gap: Val::Px(2.0),
+                                ..default()
+                            },
+                            background_color: SLOT_BUTTON_COLOR.into(),
+                            border_radius: BorderRadius::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                        SaveSlotButton { slot },
+                        Focusable::new(GameState::SaveLoad, SLOT_BUTTON_COLOR, SLOT_BUTTON_HOVER),
+                        Name::new("save_slot"),
+                    )).with_children(|button| {
+                        spawn_slot_contents(
+                            button,
+                            slot,
+                            read_meta(slot).as_ref(),
+                            manifest,
+                            &state.language,
+                            &language_resource,
+                            &language_packs,
+                            &text_styles,
+                        );
+                    });
+                }
+            });
+
+            // Back Button
+            panel.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(160.0),
+                        height: Val::Px(45.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: SLOT_BUTTON_COLOR.into(),
+                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                    ..default()
+                },
+                SaveLoadBackButton,
+                Focusable::new(GameState::SaveLoad, SLOT_BUTTON_COLOR, SLOT_BUTTON_HOVER),
+            )).with_children(|button| {
+                TextBuilder::localized_child(
+                    button,
+                    "ui.back",
+                    TextStylePreset::Button,
+                    &language_resource,
+                    &language_packs,
+                    &text_styles,
+                );
+            });
+        });
+    });
+}
+
+/// ข้อความในช่อง save: หัวข้อ (ช่อง + บท), ข้อความตัวอย่าง, เวลาที่ save และเวลาเล่น
+fn spawn_slot_contents(
+    button: &mut ChildBuilder,
+    slot: SaveSlot,
+    meta: Option<&SaveMeta>,
+    manifest: Option<&StoryManifest>,
+    language: &str,
+    language_resource: &LanguageResource,
+    language_packs: &Assets<LanguagePack>,
+    text_styles: &TextStyleResource,
+) {
+    let slot_label = match slot {
+        SaveSlot::Manual(number) => format!("{} {}", get_text(language_resource, language_packs, "ui.slot"), number),
+        SaveSlot::Quick => get_text(language_resource, language_packs, "ui.quick_save"),
+        SaveSlot::Auto(number) => format!("{} {}", get_text(language_resource, language_packs, "ui.auto_save"), number),
+    };
+    let Some(meta) = meta else {
+        TextBuilder::static_child(
+            button,
+            &slot_label,
+            TextStylePreset::Custom(18.0, true, Color::srgb(1.0, 0.8, 0.2)),
+            language_resource,
+            text_styles,
+        );
+        TextBuilder::localized_child(
+            button,
+            "ui.empty_slot",
+            TextStylePreset::Hint,
+            language_resource,
+            language_packs,
+            text_styles,
+        );
+        return;
+    };
+
+    let chapter = manifest
+        .and_then(|manifest| manifest.chapter(&meta.scene, language))
+        .unwrap_or(&meta.scene);
+    TextBuilder::static_child(
+        button,
+        &format!("{} - {}", slot_label, chapter),
+        TextStylePreset::Custom(18.0, true, Color::srgb(1.0, 0.8, 0.2)),
+        language_resource,
+        text_styles,
+    );
+    TextBuilder::static_child(
+        button,
+        &preview(&meta.text),
+        TextStylePreset::Custom(15.0, false, Color::WHITE),
+        language_resource,
+        text_styles,
+    );
+    TextBuilder::static_child(
+        button,
+        &format!("{}  |  {}", format_timestamp(meta.timestamp), format_playtime(meta.playtime)),
+        TextStylePreset::Hint,
+        language_resource,
+        text_styles,
+    );
+}
+
+/// ตัดข้อความยาวให้อยู่ในบรรทัดเดียว
+fn preview(text: &str) -> String {
+    let text = text.replace(['\n', '\u{200B}'], " ");
+    if text.chars().count() <= PREVIEW_CHARS {
+        return text;
+    }
+    let mut short: String = text.chars().take(PREVIEW_CHARS).collect();
+    short.push('…');
+    short
+}
+
+pub fn handle_save_load_button_hover(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, Or<(With<SaveSlotButton>, With<SaveLoadBackButton>)>)
+    >,
+) {
+    for (interaction, mut bg_color) in button_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => *bg_color = SLOT_BUTTON_PRESSED.into(),
+            Interaction::Hovered => *bg_color = SLOT_BUTTON_HOVER.into(),
+            Interaction::None => *bg_color = SLOT_BUTTON_COLOR.into(),
+        }
+    }
+}
+
+pub fn handle_save_load_buttons(
+    slot_query: Query<(&Interaction, &SaveSlotButton), Changed<Interaction>>,
+    back_query: Query<&Interaction, (Changed<Interaction>, With<SaveLoadBackButton>)>,
+    mode: Res<SaveLoadMode>,
+    previous_state: Res<PreviousState>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+    mut change_events: EventWriter<ChangeStateEvent>,
+) {
+    for (interaction, button) in slot_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *mode {
+            SaveLoadMode::Save => {
+                save_events.send(SaveGameEvent { slot: button.slot });
+            }
+            // ช่องว่างกดไม่ได้
+            SaveLoadMode::Load if read_meta(button.slot).is_some() => {
+                load_events.send(LoadGameEvent { slot: button.slot });
+            }
+            SaveLoadMode::Load => {}
+        }
+    }
+
+    for interaction in back_query.iter() {
+        if *interaction == Interaction::Pressed {
+            change_events.send(ChangeStateEvent {
+                new_state: previous_state.state.clone().unwrap_or(GameState::MainMenu),
+            });
+        }
+    }
+}
+
+/// เขียนข้อมูลช่องใหม่หลังบันทึก (ต้องรันหลัง `handle_save_events`)
+pub fn refresh_save_slots(
+    mut commands: Commands,
+    mut save_events: EventReader<SaveGameEvent>,
+    slot_query: Query<(Entity, &SaveSlotButton)>,
+    state: Res<VNState>,
+    story: Res<StoryResource>,
+    manifests: Res<Assets<StoryManifest>>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    text_styles: Res<TextStyleResource>,
+) {
+    for event in save_events.read() {
+        for (entity, button) in slot_query.iter().filter(|(_, button)| button.slot == event.slot) {
+            commands.entity(entity).despawn_descendants().with_children(|parent| {
+                spawn_slot_contents(
+                    parent,
+                    button.slot,
+                    read_meta(button.slot).as_ref(),
+                    manifests.get(&story.manifest),
+                    &state.language,
+                    &language_resource,
+                    &language_packs,
+                    &text_styles,
+                );
+            });
+        }
+    }
+}
+
+pub fn cleanup_save_load_ui(
+    mut commands: Commands,
+    save_load_query: Query<Entity, With<SaveLoadUI>>,
+) {
+    for entity in save_load_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
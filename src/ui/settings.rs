@@ -3,10 +3,12 @@ use bevy::window::{WindowMode, PrimaryWindow};
 use crate::core::game_state::{GameState, ChangeStateEvent};
 use crate::core::language::manager::{LanguageResource, LanguageChangeEvent, get_text};
 use crate::core::language::types::{LanguagePack, LanguageCode};
+use crate::core::dialog::auto::{MIN_AUTO_SPEED, MAX_AUTO_SPEED};
 use crate::core::resources::{SettingsResource, SettingsChangeEvent};
 use crate::core::text::styles::TextStyleResource;
 use crate::core::text::components::TextStylePreset;
 use crate::core::text::builder::TextBuilder;
+use crate::ui::focus::{FocusAdjustable, FocusState, Focusable};
 
 #[derive(Component)]
 pub struct SettingsUI;
@@ -23,9 +25,18 @@ pub struct ResolutionButton;
 #[derive(Component)]
 pub struct FullscreenButton;
 
+#[derive(Component)]
+pub struct SkipUnreadButton;
+
+#[derive(Component)]
+pub struct MarkUnreadButton;
+
 #[derive(Component)]
 pub struct ApplyButton;
 
+#[derive(Component)]
+pub struct ControlsButton;
+
 #[derive(Component)]
 pub struct CurrentLanguageText;
 
@@ -35,6 +46,22 @@ pub struct CurrentResolutionText;
 #[derive(Component)]
 pub struct CurrentFullscreenText;
 
+#[derive(Component)]
+pub struct CurrentSkipUnreadText;
+
+#[derive(Component)]
+pub struct CurrentMarkUnreadText;
+
+/// แถบ slider ความเร็วโหมด auto (คลิกหรือลากเพื่อตั้งค่า)
+#[derive(Component)]
+pub struct AutoSpeedSlider;
+
+#[derive(Component)]
+pub struct AutoSpeedFill;
+
+#[derive(Component)]
+pub struct CurrentAutoSpeedText;
+
 #[derive(Resource, Default)]
 pub struct ResolutionDropdownState {
     pub is_open: bool,
@@ -45,6 +72,10 @@ const PANEL_BG_COLOR: Color = Color::srgba(0.15, 0.15, 0.25, 0.95);
 const BUTTON_COLOR: Color = Color::srgba(0.2, 0.2, 0.3, 0.9);
 const BUTTON_HOVER: Color = Color::srgba(0.3, 0.3, 0.4, 0.9);
 const BUTTON_PRESSED: Color = Color::srgba(0.4, 0.4, 0.5, 0.9);
+const SLIDER_FILL_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const SLIDER_WIDTH: f32 = 160.0;
+/// ค่าของ slider ปัดเป็นขั้นละเท่านี้
+const AUTO_SPEED_STEP: f32 = 0.1;
 
 const RESOLUTIONS: [(f32, f32, &str); 4] = [
     (1280.0, 720.0, "HD"),
@@ -84,7 +115,7 @@ pub fn setup_settings_ui(
                 position_type: PositionType::Absolute,
                 left: Val::Percent(50.0),
                 top: Val::Percent(50.0),
-                margin: UiRect::new(Val::Px(-250.0), Val::Auto, Val::Px(-200.0), Val::Auto),
+                margin: UiRect::new(Val::Px(-250.0), Val::Auto, Val::Px(-330.0), Val::Auto),
                 flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
                 padding: UiRect::all(Val::Px(30.0)),
@@ -146,6 +177,39 @@ pub fn setup_settings_ui(
             &text_styles,
         );
 
+        // Skip Unread Setting
+        create_setting_row(
+            parent,
+            "ui.skip_unread_setting",
+            &get_text(&language_resource, &language_packs, enabled_key(settings.skip_unread)),
+            SkipUnreadButton,
+            CurrentSkipUnreadText,
+            &language_resource,
+            &language_packs,
+            &text_styles,
+        );
+
+        // Mark Unread Setting
+        create_setting_row(
+            parent,
+            "ui.mark_unread_setting",
+            &get_text(&language_resource, &language_packs, enabled_key(settings.mark_unread)),
+            MarkUnreadButton,
+            CurrentMarkUnreadText,
+            &language_resource,
+            &language_packs,
+            &text_styles,
+        );
+
+        // Auto Speed Setting
+        create_auto_speed_row(
+            parent,
+            settings.auto_speed,
+            &language_resource,
+            &language_packs,
+            &text_styles,
+        );
+
         // Buttons
         parent.spawn(NodeBundle {
             style: Style {
@@ -165,6 +229,14 @@ pub fn setup_settings_ui(
                 &language_packs,
                 &text_styles,
             );
+            create_button(
+                buttons,
+                "ui.controls_setting",
+                ControlsButton,
+                &language_resource,
+                &language_packs,
+                &text_styles,
+            );
             create_button(
                 buttons,
                 "ui.apply_settings",
@@ -245,6 +317,7 @@ fn create_setting_row<B: Component, T: Component>(
                     ..default()
                 },
                 button_component,
+                Focusable::new(GameState::Settings, BUTTON_COLOR, BUTTON_HOVER),
             )).with_children(|button| {
                 TextBuilder::localized_child(
                     button,
@@ -259,6 +332,94 @@ fn create_setting_row<B: Component, T: Component>(
     });
 }
 
+fn create_auto_speed_row(
+    parent: &mut ChildBuilder,
+    auto_speed: f32,
+    language_resource: &LanguageResource,
+    language_packs: &Assets<LanguagePack>,
+    text_styles: &TextStyleResource,
+) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(15.0)),
+            ..default()
+        },
+        background_color: Color::srgba(0.1, 0.1, 0.2, 0.5).into(),
+        border_radius: BorderRadius::all(Val::Px(8.0)),
+        ..default()
+    }).with_children(|row| {
+        TextBuilder::localized_child(
+            row,
+            "ui.auto_speed_setting",
+            TextStylePreset::Custom(20.0, true, Color::WHITE),
+            language_resource,
+            language_packs,
+            text_styles,
+        );
+
+        row.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }).with_children(|value_section| {
+            TextBuilder::static_child_with_components(
+                value_section,
+                &format_auto_speed(auto_speed),
+                TextStylePreset::Custom(18.0, false, Color::srgba(0.8, 0.8, 0.9, 1.0)),
+                language_resource,
+                text_styles,
+                CurrentAutoSpeedText,
+            );
+
+            value_section.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(SLIDER_WIDTH),
+                        height: Val::Px(14.0),
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    border_radius: BorderRadius::all(Val::Px(7.0)),
+                    ..default()
+                },
+                AutoSpeedSlider,
+                Focusable::new(GameState::Settings, BUTTON_COLOR, BUTTON_HOVER),
+                FocusAdjustable,
+            )).with_children(|track| {
+                track.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(auto_speed_fraction(auto_speed) * 100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: SLIDER_FILL_COLOR.into(),
+                        border_radius: BorderRadius::all(Val::Px(7.0)),
+                        ..default()
+                    },
+                    AutoSpeedFill,
+                ));
+            });
+        });
+    });
+}
+
+fn format_auto_speed(auto_speed: f32) -> String {
+    format!("x{:.1}", auto_speed)
+}
+
+fn auto_speed_fraction(auto_speed: f32) -> f32 {
+    ((auto_speed - MIN_AUTO_SPEED) / (MAX_AUTO_SPEED - MIN_AUTO_SPEED)).clamp(0.0, 1.0)
+}
+
 fn create_button<T: Component>(
     parent: &mut ChildBuilder,
     text_key: &str,
@@ -281,6 +442,7 @@ fn create_button<T: Component>(
             ..default()
         },
         component,
+        Focusable::new(GameState::Settings, BUTTON_COLOR, BUTTON_HOVER),
     )).with_children(|button| {
         TextBuilder::localized_child(
             button,
@@ -301,6 +463,10 @@ fn format_language(language: &LanguageCode) -> String {
     }
 }
 
+fn enabled_key(enabled: bool) -> &'static str {
+    if enabled { "ui.enabled" } else { "ui.disabled" }
+}
+
 fn get_next_resolution(current: (f32, f32)) -> (f32, f32) {
     let current_index = RESOLUTIONS.iter()
         .position(|(w, h, _)| *w == current.0 && *h == current.1)
@@ -324,6 +490,7 @@ pub fn handle_settings_button_hover(
 pub fn handle_settings_buttons(
     back_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
     apply_query: Query<&Interaction, (Changed<Interaction>, With<ApplyButton>)>,
+    controls_query: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
     language_query: Query<&Interaction, (Changed<Interaction>, With<LanguageButton>)>,
     resolution_query: Query<&Interaction, (Changed<Interaction>, With<ResolutionButton>)>,
     fullscreen_query: Query<&Interaction, (Changed<Interaction>, With<FullscreenButton>)>,
@@ -342,6 +509,14 @@ pub fn handle_settings_buttons(
         }
     }
 
+    for interaction in controls_query.iter() {
+        if *interaction == Interaction::Pressed {
+            change_events.send(ChangeStateEvent {
+                new_state: GameState::KeyBindings,
+            });
+        }
+    }
+
     for interaction in apply_query.iter() {
         if *interaction == Interaction::Pressed {
             if let Ok(mut window) = window_query.get_single_mut() {
@@ -352,7 +527,10 @@ pub fn handle_settings_buttons(
                     WindowMode::Windowed
                 };
             }
-            info!("Settings applied!");
+            match settings.save() {
+                Ok(()) => info!("Settings applied and saved"),
+                Err(err) => error!("Failed to save settings: {:#}", err),
+            }
         }
     }
 
@@ -383,6 +561,27 @@ pub fn handle_settings_buttons(
     }
 }
 
+pub fn handle_read_settings_buttons(
+    skip_unread_query: Query<&Interaction, (Changed<Interaction>, With<SkipUnreadButton>)>,
+    mark_unread_query: Query<&Interaction, (Changed<Interaction>, With<MarkUnreadButton>)>,
+    mut settings: ResMut<SettingsResource>,
+    mut settings_events: EventWriter<SettingsChangeEvent>,
+) {
+    for interaction in skip_unread_query.iter() {
+        if *interaction == Interaction::Pressed {
+            settings.skip_unread = !settings.skip_unread;
+            settings_events.send(SettingsChangeEvent);
+        }
+    }
+
+    for interaction in mark_unread_query.iter() {
+        if *interaction == Interaction::Pressed {
+            settings.mark_unread = !settings.mark_unread;
+            settings_events.send(SettingsChangeEvent);
+        }
+    }
+}
+
 pub fn update_settings_values(
     mut settings_events: EventReader<SettingsChangeEvent>,
     language_resource: Res<LanguageResource>,
@@ -408,6 +607,87 @@ pub fn update_settings_values(
     }
 }
 
+/// กดค้างบน slider แล้วลากเพื่อเปลี่ยนค่า
+pub fn handle_auto_speed_slider(
+    slider_query: Query<(&Interaction, &Node, &GlobalTransform), With<AutoSpeedSlider>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut settings: ResMut<SettingsResource>,
+    mut settings_events: EventWriter<SettingsChangeEvent>,
+) {
+    let Some(cursor) = window_query.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+
+    for (interaction, node, transform) in slider_query.iter() {
+        let width = node.size().x;
+        if *interaction != Interaction::Pressed || width <= 0.0 {
+            continue;
+        }
+        let left = transform.translation().x - width / 2.0;
+        let fraction = ((cursor.x - left) / width).clamp(0.0, 1.0);
+        let speed = MIN_AUTO_SPEED + fraction * (MAX_AUTO_SPEED - MIN_AUTO_SPEED);
+        let speed = (speed / AUTO_SPEED_STEP).round() * AUTO_SPEED_STEP;
+        if (speed - settings.auto_speed).abs() > f32::EPSILON {
+            settings.auto_speed = speed;
+            settings_events.send(SettingsChangeEvent);
+        }
+    }
+}
+
+/// ซ้าย/ขวาตอน focus อยู่ที่ slider ปรับความเร็ว auto ทีละ `AUTO_SPEED_STEP`
+pub fn handle_auto_speed_focus(
+    focus: Res<FocusState>,
+    slider_query: Query<(), With<AutoSpeedSlider>>,
+    mut settings: ResMut<SettingsResource>,
+    mut settings_events: EventWriter<SettingsChangeEvent>,
+) {
+    if focus.adjust == 0 || !focus.focused.is_some_and(|entity| slider_query.contains(entity)) {
+        return;
+    }
+    let speed = settings.auto_speed + f32::from(focus.adjust) * AUTO_SPEED_STEP;
+    let speed = ((speed / AUTO_SPEED_STEP).round() * AUTO_SPEED_STEP).clamp(MIN_AUTO_SPEED, MAX_AUTO_SPEED);
+    if (speed - settings.auto_speed).abs() > f32::EPSILON {
+        settings.auto_speed = speed;
+        settings_events.send(SettingsChangeEvent);
+    }
+}
+
+pub fn update_auto_speed_slider(
+    mut settings_events: EventReader<SettingsChangeEvent>,
+    settings: Res<SettingsResource>,
+    mut text_query: Query<&mut Text, With<CurrentAutoSpeedText>>,
+    mut fill_query: Query<&mut Style, With<AutoSpeedFill>>,
+) {
+    if settings_events.read().count() == 0 {
+        return;
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format_auto_speed(settings.auto_speed);
+    }
+    if let Ok(mut style) = fill_query.get_single_mut() {
+        style.width = Val::Percent(auto_speed_fraction(settings.auto_speed) * 100.0);
+    }
+}
+
+pub fn update_read_settings_values(
+    mut settings_events: EventReader<SettingsChangeEvent>,
+    language_resource: Res<LanguageResource>,
+    language_packs: Res<Assets<LanguagePack>>,
+    settings: Res<SettingsResource>,
+    mut skip_unread_query: Query<&mut Text, (With<CurrentSkipUnreadText>, Without<CurrentMarkUnreadText>)>,
+    mut mark_unread_query: Query<&mut Text, (With<CurrentMarkUnreadText>, Without<CurrentSkipUnreadText>)>,
+) {
+    for _event in settings_events.read() {
+        if let Ok(mut text) = skip_unread_query.get_single_mut() {
+            text.sections[0].value = get_text(&language_resource, &language_packs, enabled_key(settings.skip_unread));
+        }
+
+        if let Ok(mut text) = mark_unread_query.get_single_mut() {
+            text.sections[0].value = get_text(&language_resource, &language_packs, enabled_key(settings.mark_unread));
+        }
+    }
+}
+
 pub fn cleanup_settings(
     mut commands: Commands,
     settings_query: Query<Entity, With<SettingsUI>>,
@@ -31,4 +31,8 @@ pub fn dialog(path: &str) -> Identifier {
 
 pub fn language(path: &str) -> Identifier {
     of("lang", path)
-}
\ No newline at end of file
+}
+
+pub fn audio(path: &str) -> Identifier {
+    of("audio", path)
+}
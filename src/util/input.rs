@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use crate::core::dialog::typewriter::TypewriterText;
+use crate::core::input::action::{ActionState, InputAction};
 use crate::ui::dialog::DialogBox;
 
 pub struct InputEvent {
@@ -9,28 +10,18 @@ pub struct InputEvent {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InputSource {
-    MouseClick,
-    TouchScreen,
-    KeyPress(KeyCode),
+    Action(InputAction),
     UIInteraction,
 }
 
 pub fn detect_interaction(
-    mouse: &Res<ButtonInput<MouseButton>>,
-    touch: &Res<Touches>,
+    actions: &ActionState,
     dialog_box_query: &Query<&Interaction, (With<DialogBox>, Changed<Interaction>)>,
 ) -> Option<InputEvent> {
-    if mouse.just_pressed(MouseButton::Left) {
+    if actions.just_pressed(InputAction::Advance) {
         return Some(InputEvent {
             pressed: true,
-            source: InputSource::MouseClick,
-        });
-    }
-
-    if touch.iter_just_pressed().next().is_some() {
-        return Some(InputEvent {
-            pressed: true,
-            source: InputSource::TouchScreen,
+            source: InputSource::Action(InputAction::Advance),
         });
     }
 
@@ -45,5 +36,5 @@ pub fn detect_interaction(
 }
 
 pub fn is_dialog_text_finished(typewriter: &TypewriterText) -> bool {
-    typewriter.char_index >= typewriter.full_text.chars().count()
+    typewriter.is_finished()
 }
@@ -1,2 +1,3 @@
 pub mod identifier;
-pub mod input;
\ No newline at end of file
+pub mod input;
+pub mod paths;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use std::env;
+use std::path::PathBuf;
+
+/// ชื่อโฟลเดอร์ของเกมในโฟลเดอร์ข้อมูลของผู้ใช้
+const APP_DIR: &str = "helium";
+
+/// โฟลเดอร์ข้อมูลของผู้ใช้ตาม OS
+///
+/// - Windows: `%APPDATA%\helium`
+/// - macOS: `~/Library/Application Support/helium`
+/// - อื่นๆ: `$XDG_DATA_HOME/helium` หรือ `~/.local/share/helium`
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| home_dir().map(|home| home.join(".local/share")))
+    };
+    base.map(|base| base.join(APP_DIR))
+}
+
+/// โฟลเดอร์ config ของผู้ใช้ตาม OS
+///
+/// - Windows: `%APPDATA%\helium`
+/// - macOS: `~/Library/Application Support/helium`
+/// - อื่นๆ: `$XDG_CONFIG_HOME/helium` หรือ `~/.config/helium`
+pub fn config_dir() -> Option<PathBuf> {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        return data_dir();
+    }
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+        .map(|base| base.join(APP_DIR))
+}
+
+/// โฟลเดอร์ของไฟล์ save
+pub fn save_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("saves"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from)
+}
@@ -0,0 +1,102 @@
+//! ทดสอบ gamepad กับ action map และ focus แบบ headless (ไม่เปิดหน้าต่าง)
+
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::window::CursorMoved;
+use helium::core::game_state::GameState;
+use helium::core::input::action::{update_action_state, ActionState, InputAction, InputMap};
+use helium::core::input::binding::InputBinding;
+use helium::core::resources::SettingsResource;
+use helium::ui::focus::{navigate_focus, FocusState, Focusable};
+
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .init_state::<GameState>()
+        .init_resource::<ActionState>()
+        .init_resource::<SettingsResource>()
+        .init_resource::<FocusState>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<ButtonInput<GamepadButton>>()
+        .init_resource::<Touches>()
+        .add_event::<MouseWheel>()
+        .add_event::<CursorMoved>()
+        .add_systems(Update, (update_action_state, navigate_focus).chain());
+    app
+}
+
+fn spawn_button(app: &mut App, y: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            Focusable::new(GameState::MainMenu, Color::BLACK, Color::WHITE),
+            GlobalTransform::from_xyz(0.0, y, 0.0),
+            InheritedVisibility::VISIBLE,
+            Interaction::None,
+        ))
+        .id()
+}
+
+/// ปล่อยทุกปุ่ม กด `button` ของ gamepad ตัวแรก แล้วรัน 1 frame
+fn press(app: &mut App, button: GamepadButtonType) {
+    let mut input = app.world_mut().resource_mut::<ButtonInput<GamepadButton>>();
+    input.reset_all();
+    input.press(GamepadButton::new(Gamepad::new(0), button));
+    app.update();
+}
+
+#[test]
+fn gamepad_buttons_trigger_actions() {
+    let mut app = headless_app();
+
+    press(&mut app, GamepadButtonType::South);
+    let actions = app.world().resource::<ActionState>();
+    assert!(actions.just_pressed(InputAction::Advance));
+    assert!(!actions.just_pressed(InputAction::NavigateDown));
+
+    press(&mut app, GamepadButtonType::DPadDown);
+    let actions = app.world().resource::<ActionState>();
+    assert!(actions.just_pressed(InputAction::NavigateDown));
+    assert!(!actions.just_pressed(InputAction::Advance));
+}
+
+#[test]
+fn dpad_moves_focus_and_south_presses_button() {
+    let mut app = headless_app();
+    let first = spawn_button(&mut app, 100.0);
+    let second = spawn_button(&mut app, 200.0);
+
+    press(&mut app, GamepadButtonType::DPadDown);
+    assert_eq!(app.world().resource::<FocusState>().focused, Some(first));
+    press(&mut app, GamepadButtonType::DPadDown);
+    assert_eq!(app.world().resource::<FocusState>().focused, Some(second));
+    press(&mut app, GamepadButtonType::DPadDown);
+    assert_eq!(app.world().resource::<FocusState>().focused, Some(first));
+
+    press(&mut app, GamepadButtonType::South);
+    assert_eq!(*app.world().get::<Interaction>(first).unwrap(), Interaction::Pressed);
+
+    // ปุ่มที่กดผ่าน focus กลับเป็น `None` ใน frame ถัดไป
+    app.world_mut().resource_mut::<ButtonInput<GamepadButton>>().reset_all();
+    app.update();
+    assert_eq!(*app.world().get::<Interaction>(first).unwrap(), Interaction::None);
+}
+
+#[test]
+fn rebind_replaces_only_the_same_device() {
+    let mut map = InputMap::default();
+    map.rebind(InputAction::Auto, InputBinding::Key(KeyCode::KeyQ));
+    assert_eq!(
+        map.bindings(InputAction::Auto),
+        [InputBinding::Key(KeyCode::KeyQ), InputBinding::Gamepad(GamepadButtonType::North)]
+    );
+
+    // ปุ่มที่ตั้งใหม่ถูกเอาออกจาก action เดิม
+    map.rebind(InputAction::Auto, InputBinding::Gamepad(GamepadButtonType::West));
+    assert_eq!(
+        map.bindings(InputAction::Auto),
+        [InputBinding::Key(KeyCode::KeyQ), InputBinding::Gamepad(GamepadButtonType::West)]
+    );
+    assert_eq!(map.bindings(InputAction::Backlog), [InputBinding::Key(KeyCode::KeyH)]);
+}